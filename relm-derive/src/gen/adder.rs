@@ -22,6 +22,9 @@
 //! The adder adds the calls to set_property() or emit(Msg) whenever we assign to an attribute of
 //! the model.
 
+use std::collections::HashSet;
+
+use proc_macro2::TokenStream;
 use quote::{quote, quote_spanned, TokenStreamExt};
 use syn::{
     Expr,
@@ -31,6 +34,7 @@ use syn::{
     ExprField,
     ExprPath,
     Ident,
+    LitStr,
     Path,
     Stmt,
     parse,
@@ -40,15 +44,22 @@ use syn::fold::{Fold, fold_expr};
 use syn::Member::Named;
 
 use super::{MsgModelMap, PropertyModelMap};
+use super::computed::{ComputedDeps, MemoizedCalls};
 
 pub struct Adder<'a> {
+    computed_deps: &'a ComputedDeps,
+    mirrored_attributes: &'a HashSet<Ident>,
     msg_map: &'a MsgModelMap,
     property_map: &'a PropertyModelMap,
 }
 
 impl<'a> Adder<'a> {
-    pub fn new(property_map: &'a PropertyModelMap, msg_map: &'a MsgModelMap) -> Self {
+    pub fn new(property_map: &'a PropertyModelMap, msg_map: &'a MsgModelMap, computed_deps: &'a ComputedDeps,
+        mirrored_attributes: &'a HashSet<Ident>) -> Self
+    {
         Adder {
+            computed_deps,
+            mirrored_attributes,
             msg_map,
             property_map,
         }
@@ -56,12 +67,22 @@ impl<'a> Adder<'a> {
 }
 
 impl<'a> Adder<'a> {
+    fn create_stmts(&self, ident: &Ident) -> Vec<Stmt> {
+        let mut stmts = vec![];
+        // The memoized values must be invalidated before they are used to set the properties.
+        stmts.append(&mut create_stmts_for_computed(ident, self.computed_deps));
+        stmts.append(&mut create_stmts_for_mirror(ident, self.mirrored_attributes));
+        stmts.append(&mut create_stmts_for_props(ident, self.property_map, self.computed_deps));
+        stmts.append(&mut create_stmts_for_msgs(ident, self.msg_map, self.computed_deps));
+        stmts
+    }
+
     fn fold_assign(&self, lhs: Expr, mut new_assign: Expr) -> Expr {
         let mut statements = vec![];
         let new_statements =
             if let Field(ExprField { ref base, member: Named(ref ident), .. }) = lhs {
                 if is_model_path(base) {
-                    Some(create_stmts(ident, self.property_map, self.msg_map))
+                    Some(self.create_stmts(ident))
                 }
                 else {
                     None
@@ -107,24 +128,53 @@ pub struct Message {
 pub struct Property {
     pub expr: Expr,
     pub is_relm_widget: bool,
+    pub kind: PropertyKind,
     pub name: Ident,
     pub widget_name: Ident,
 }
 
-fn create_stmts(ident: &Ident, property_map: &PropertyModelMap, msg_map: &MsgModelMap) -> Vec<Stmt> {
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum PropertyKind {
+    /// Two-way binding (`property <=> self.model.attribute`), set with ObjectExt::set_property().
+    Binding,
+    /// Property set with its setter method.
+    Setter,
+    /// Style class added when the expression is true and removed otherwise.
+    StyleClass(LitStr),
+}
+
+fn create_stmts_for_mirror(ident: &Ident, mirrored_attributes: &HashSet<Ident>) -> Vec<Stmt> {
+    let mut stmts = vec![];
+    if mirrored_attributes.contains(ident) {
+        let attribute = ident.to_string();
+        let stmt = quote_spanned! { ident.span() =>
+            self.mirrors.update(#attribute, &self.model);
+        };
+        stmts.push(parse(stmt.into()).expect("parse() in create_stmts_for_mirror"));
+    }
+    stmts
+}
+
+fn create_stmts_for_computed(ident: &Ident, computed_deps: &ComputedDeps) -> Vec<Stmt> {
     let mut stmts = vec![];
-    stmts.append(&mut create_stmts_for_props(ident, property_map));
-    stmts.append(&mut create_stmts_for_msgs(ident, msg_map));
+    for (method, deps) in computed_deps {
+        if deps.contains(ident) {
+            let stmt = quote_spanned! { ident.span() =>
+                *self.computed.#method.borrow_mut() = None;
+            };
+            stmts.push(parse(stmt.into()).expect("parse() in create_stmts_for_computed"));
+        }
+    }
     stmts
 }
 
-fn create_stmts_for_msgs(ident: &Ident, msg_map: &MsgModelMap) -> Vec<Stmt> {
+fn create_stmts_for_msgs(ident: &Ident, msg_map: &MsgModelMap, computed_deps: &ComputedDeps) -> Vec<Stmt> {
     let mut stmts = vec![];
     if let Some(messages) = msg_map.get(ident) {
         for msg in messages {
             let widget_name = &msg.widget_name;
             let mut value = quote! {};
-            value.append_all(&[&msg.expr]);
+            value.append_all(&[MemoizedCalls::new(computed_deps).fold_expr(msg.expr.clone())]);
             let variant = &msg.name;
             let stmt = quote_spanned! { ident.span() =>
                 { self.components.#widget_name.stream().emit(#variant(#value)); }
@@ -139,17 +189,33 @@ fn create_stmts_for_msgs(ident: &Ident, msg_map: &MsgModelMap) -> Vec<Stmt> {
     stmts
 }
 
-fn create_stmts_for_props(ident: &Ident, property_map: &PropertyModelMap) -> Vec<Stmt> {
+fn create_stmts_for_props(ident: &Ident, property_map: &PropertyModelMap, computed_deps: &ComputedDeps) -> Vec<Stmt> {
     let mut stmts = vec![];
     if let Some(properties) = property_map.get(ident) {
         for property in properties {
             let widget_name = &property.widget_name;
-            let prop_name = Ident::new(&format!("set_{}", property.name), property.name.span());
             let mut tokens = quote! {};
-            tokens.append_all(&[&property.expr]);
+            tokens.append_all(&[MemoizedCalls::new(computed_deps).fold_expr(property.expr.clone())]);
             let stmt =
-                quote_spanned! { ident.span() =>
-                    { self.widgets.#widget_name.#prop_name(#tokens); }
+                match property.kind {
+                    PropertyKind::Binding => {
+                        let prop_name = property.name.to_string().replace('_', "-");
+                        quote_spanned! { ident.span() =>
+                            { ::relm::ObjectExt::set_property(&self.widgets.#widget_name, #prop_name, #tokens); }
+                        }
+                    },
+                    PropertyKind::Setter => {
+                        let prop_name = Ident::new(&format!("set_{}", property.name), property.name.span());
+                        quote_spanned! { ident.span() =>
+                            { self.widgets.#widget_name.#prop_name(#tokens); }
+                        }
+                    },
+                    PropertyKind::StyleClass(ref class) => {
+                        let toggle = gen_toggle_style_class(&quote! { self.widgets.#widget_name }, class, &tokens);
+                        quote_spanned! { ident.span() =>
+                            { #toggle; }
+                        }
+                    },
                 };
             let expr: Expr = parse(stmt.into()).expect("parse() in create_stmts");
             if let Block(ExprBlock { ref block, .. }) = expr {
@@ -160,6 +226,20 @@ fn create_stmts_for_props(ident: &Ident, property_map: &PropertyModelMap) -> Vec
     stmts
 }
 
+/// Generate the expression adding the style `class` to `widget` when `condition` is true and
+/// removing it otherwise.
+pub fn gen_toggle_style_class(widget: &TokenStream, class: &LitStr, condition: &TokenStream) -> TokenStream {
+    quote! {{
+        let context = ::gtk::prelude::WidgetExt::style_context(&#widget);
+        if #condition {
+            ::gtk::prelude::StyleContextExt::add_class(&context, #class);
+        }
+        else {
+            ::gtk::prelude::StyleContextExt::remove_class(&context, #class);
+        }
+    }}
+}
+
 fn is_model_path(expr: &Expr) -> bool {
     if let Field(ExprField { ref base, ref member, .. }) = *expr {
         if let Expr::Path(ExprPath { path: Path { ref segments, .. }, ..}) = **base {
@@ -0,0 +1,267 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Computed values: the #[computed] methods derive a value from the model attributes.
+//! Calling them always computes the value, since the model can be mutated in place, but the
+//! properties set after an assignment use a result memoized until one of the model attributes they
+//! read is assigned.
+
+use std::collections::{HashMap, HashSet};
+
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::{
+    Attribute,
+    Block,
+    Expr,
+    FnArg,
+    Ident,
+    ImplItem,
+    ImplItemMethod,
+    Macro,
+    Receiver,
+    ReturnType,
+    Type,
+    Visibility,
+    parse,
+};
+use syn::fold::{Fold, fold_expr};
+use syn::parse::{Error, Result};
+use syn::spanned::Spanned;
+use syn::visit::Visit;
+
+use super::block_to_impl_item;
+use super::parser::{EitherWidget, Widget};
+use super::transformer::{Transformer, fold_macro_exprs};
+use super::walker::{ModelVariableVisitor, SelfCallVisitor, self_call};
+
+/// Map each #[computed] method to the model attributes it reads, directly or through other
+/// #[computed] methods.
+pub type ComputedDeps = HashMap<Ident, HashSet<Ident>>;
+
+#[derive(Debug)]
+pub struct ComputedMethod {
+    attrs: Vec<Attribute>,
+    block: Block,
+    name: Ident,
+    output: Type,
+    vis: Visibility,
+}
+
+impl ComputedMethod {
+    pub fn new(method: ImplItemMethod) -> Result<Self> {
+        let ImplItemMethod { attrs, vis, sig, block, .. } = method;
+        let span = sig.span();
+        let is_ref_self = matches!(sig.inputs.first(), Some(FnArg::Receiver(Receiver { reference: Some(_), mutability: None, .. })));
+        let output =
+            match sig.output {
+                ReturnType::Type(_, ty) if is_ref_self && sig.inputs.len() == 1 && sig.generics.params.is_empty() &&
+                    sig.asyncness.is_none() => *ty,
+                _ => return Err(Error::new(span, "a #[computed] method must have the signature `fn name(&self) -> Type`")),
+            };
+        Ok(ComputedMethod {
+            attrs: attrs.into_iter().filter(|attr| !attr.path.is_ident("computed")).collect(),
+            block,
+            name: sig.ident,
+            output,
+            vis,
+        })
+    }
+}
+
+/// Inline the calls to the #[computed] methods in the view, where self does not exist yet.
+/// The Transformer must be applied afterwards to replace self.model by a reference to the model.
+pub struct ComputedInliner<'a> {
+    computed_deps: &'a ComputedDeps,
+}
+
+impl<'a> ComputedInliner<'a> {
+    pub fn new(computed_deps: &'a ComputedDeps) -> Self {
+        ComputedInliner {
+            computed_deps,
+        }
+    }
+
+    pub fn inline_widget(&mut self, widget: &mut Widget) {
+        // The child properties are not inlined since they are set in on_add(), where self exists.
+        let exprs = widget.properties.values_mut()
+            .chain(widget.init_parameters.iter_mut())
+            .chain(widget.dynamic_style_classes.iter_mut().map(|(_, condition)| condition));
+        for expr in exprs {
+            *expr = self.fold_expr(expr.clone());
+        }
+        match widget.widget {
+            EitherWidget::Gtk(ref mut gtk_widget) => {
+                for expr in gtk_widget.construct_properties.values_mut() {
+                    *expr = self.fold_expr(expr.clone());
+                }
+            },
+            EitherWidget::Relm(ref mut relm_widget) => {
+                for expr in relm_widget.messages.values_mut() {
+                    *expr = self.fold_expr(expr.clone());
+                }
+            },
+        }
+        for nested_view in widget.nested_views.values_mut() {
+            self.inline_widget(nested_view);
+        }
+        for child in &mut widget.children {
+            self.inline_widget(child);
+        }
+    }
+}
+
+impl<'a> Fold for ComputedInliner<'a> {
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        if let Some(method) = self_call(&expr) {
+            if self.computed_deps.contains_key(method) {
+                let compute = compute_method_name(method);
+                let tokens = quote_spanned! { expr.span() =>
+                    Self::#compute(self.model)
+                };
+                return parse(tokens.into()).expect("computed method call");
+            }
+        }
+        fold_expr(self, expr)
+    }
+
+    fn fold_macro(&mut self, mac: Macro) -> Macro {
+        fold_macro_exprs(self, mac)
+    }
+}
+
+/// Replace the calls to the #[computed] methods by calls to their memoized version in the
+/// expressions of the properties set by the adder.
+pub struct MemoizedCalls<'a> {
+    computed_deps: &'a ComputedDeps,
+}
+
+impl<'a> MemoizedCalls<'a> {
+    pub fn new(computed_deps: &'a ComputedDeps) -> Self {
+        MemoizedCalls {
+            computed_deps,
+        }
+    }
+}
+
+impl<'a> Fold for MemoizedCalls<'a> {
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        if let Some(method) = self_call(&expr) {
+            if self.computed_deps.contains_key(method) {
+                let memoized = memoized_method_name(method);
+                let tokens = quote_spanned! { expr.span() =>
+                    self.#memoized()
+                };
+                return parse(tokens.into()).expect("memoized method call");
+            }
+        }
+        fold_expr(self, expr)
+    }
+
+    fn fold_macro(&mut self, mac: Macro) -> Macro {
+        fold_macro_exprs(self, mac)
+    }
+}
+
+/// Get the model attributes read by each #[computed] method, following the calls to the other
+/// #[computed] methods.
+pub fn get_computed_deps(methods: &[ComputedMethod]) -> ComputedDeps {
+    let mut computed_deps = ComputedDeps::new();
+    let mut calls = HashMap::new();
+    for method in methods {
+        let mut visitor = ModelVariableVisitor::new();
+        visitor.visit_block(&method.block);
+        computed_deps.insert(method.name.clone(), visitor.idents.into_iter().collect());
+        let mut visitor = SelfCallVisitor::new();
+        visitor.visit_block(&method.block);
+        calls.insert(method.name.clone(), visitor.idents);
+    }
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (method, callees) in &calls {
+            for callee in callees {
+                let callee_deps = computed_deps.get(callee).cloned().unwrap_or_default();
+                let deps = computed_deps.get_mut(method).expect("computed method deps");
+                let len = deps.len();
+                deps.extend(callee_deps);
+                changed |= deps.len() != len;
+            }
+        }
+    }
+    computed_deps
+}
+
+/// Generate, for each #[computed] method, the function computing the value from the model, the
+/// method calling it and the method returning the memoized value.
+pub fn gen_computed_methods(methods: Vec<ComputedMethod>, computed_deps: &ComputedDeps) -> Vec<ImplItem> {
+    let mut items = vec![];
+    for ComputedMethod { attrs, block, name, output, vis } in methods {
+        let compute = compute_method_name(&name);
+        let memoized = memoized_method_name(&name);
+        let block = ComputedInliner::new(computed_deps).fold_block(block);
+        let block = Transformer::new("model").fold_block(block);
+        items.push(block_to_impl_item(quote_spanned! { name.span() =>
+            fn #compute(model: &<Self as ::relm::Update>::Model) -> #output #block
+        }));
+        // The view inlines the computation and the properties use the memoized value, so these
+        // methods can be unused.
+        items.push(block_to_impl_item(quote_spanned! { name.span() =>
+            #(#attrs)*
+            #[allow(dead_code)]
+            #vis fn #name(&self) -> #output {
+                Self::#compute(&self.model)
+            }
+        }));
+        items.push(block_to_impl_item(quote_spanned! { name.span() =>
+            #[allow(dead_code)]
+            fn #memoized(&self) -> #output {
+                let mut value = self.computed.#name.borrow_mut();
+                ::std::clone::Clone::clone(value.get_or_insert_with(|| Self::#compute(&self.model)))
+            }
+        }));
+    }
+    items
+}
+
+/// Generate the struct holding the memoized values of the #[computed] methods.
+pub fn gen_computed_struct(computed_name: &Ident, methods: &[ComputedMethod]) -> TokenStream {
+    let names = methods.iter().map(|method| &method.name);
+    let outputs = methods.iter().map(|method| &method.output);
+    quote! {
+        #[derive(Default)]
+        pub struct #computed_name {
+            #(#names: ::std::cell::RefCell<Option<#outputs>>,)*
+        }
+    }
+}
+
+pub fn is_computed(method: &ImplItemMethod) -> bool {
+    method.attrs.iter().any(|attr| attr.path.is_ident("computed"))
+}
+
+fn compute_method_name(name: &Ident) -> Ident {
+    Ident::new(&format!("__relm_compute_{}", name), Span::call_site())
+}
+
+fn memoized_method_name(name: &Ident) -> Ident {
+    Ident::new(&format!("__relm_memoized_{}", name), Span::call_site())
+}
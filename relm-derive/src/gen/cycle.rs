@@ -0,0 +1,120 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Detection of the event cycles: a widget having a property depending on a model attribute and a
+//! change signal sending a message that assigns this same attribute in update() will cause an
+//! infinite loop at runtime.
+
+use proc_macro2::TokenStream;
+use syn::{
+    Expr,
+    ExprCall,
+    ExprPath,
+    Ident,
+    ImplItem,
+    ImplItemMethod,
+};
+use syn::visit::Visit;
+
+use super::{PropertyModelMap, gen_warning};
+use super::adder::PropertyKind;
+use super::parser::EitherWidget::Gtk;
+use super::parser::EventValue::CurrentWidget;
+use super::parser::EventValueReturn::{Return, WithoutReturn};
+use super::parser::Widget;
+use super::walker::{ModelAssignVisitor, path_variant};
+
+/*
+ * Signals emitted when a property is changed, be it by the user or by a setter.
+ */
+const CHANGE_SIGNALS: &[&str] = &["changed", "state_set", "toggled"];
+
+/// Generate a warning for every event cycle found in the widgets.
+pub fn gen_event_cycle_warnings(widgets: &[Widget], properties_model_map: &PropertyModelMap,
+    update_method: Option<&ImplItem>) -> Vec<TokenStream>
+{
+    let mut visitor = ModelAssignVisitor::new();
+    if let Some(ImplItem::Method(ImplItemMethod { ref block, .. })) = update_method {
+        visitor.visit_block(block);
+    }
+    let mut warnings = vec![];
+    for widget in widgets {
+        widget_warnings(widget, &visitor, properties_model_map, &mut warnings);
+    }
+    warnings
+}
+
+fn widget_warnings(widget: &Widget, visitor: &ModelAssignVisitor, properties_model_map: &PropertyModelMap,
+    warnings: &mut Vec<TokenStream>)
+{
+    if let Gtk(ref gtk_widget) = widget.widget {
+        for (signal, event) in &gtk_widget.events {
+            if !is_change_signal(signal) {
+                continue;
+            }
+            let message =
+                match event.value {
+                    CurrentWidget(WithoutReturn(ref message)) => message,
+                    CurrentWidget(Return(ref value)) => &value.0,
+                    _ => continue,
+                };
+            let variant =
+                match message_variant(message) {
+                    Some(variant) => variant,
+                    None => continue,
+                };
+            let attributes = visitor.assigned_attributes.get(&variant).into_iter().flatten();
+            for attribute in attributes {
+                let properties = properties_model_map.get(attribute).into_iter().flatten()
+                    .filter(|property| property.widget_name == widget.name)
+                    // Toggling a style class does not emit any change signal.
+                    .filter(|property| !matches!(property.kind, PropertyKind::StyleClass(_)));
+                for property in properties {
+                    let note = format!("event cycle: the `{}` signal sends `{}` which assigns `self.model.{}` \
+                        which sets the `{}` property of the same widget, emitting `{}` again",
+                        signal, variant, attribute, property.name, signal);
+                    warnings.push(gen_warning(signal.span(), &note));
+                }
+            }
+        }
+    }
+
+    for nested_view in widget.nested_views.values() {
+        widget_warnings(nested_view, visitor, properties_model_map, warnings);
+    }
+
+    for child in &widget.children {
+        widget_warnings(child, visitor, properties_model_map, warnings);
+    }
+}
+
+fn is_change_signal(signal: &Ident) -> bool {
+    let signal = signal.to_string();
+    CHANGE_SIGNALS.contains(&signal.as_str()) || signal.ends_with("_changed") || signal.starts_with("notify")
+}
+
+fn message_variant(message: &Expr) -> Option<Ident> {
+    match *message {
+        Expr::Call(ExprCall { ref func, .. }) => message_variant(func),
+        Expr::Path(ExprPath { ref path, .. }) => path_variant(path),
+        _ => None,
+    }
+}
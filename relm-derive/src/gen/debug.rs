@@ -0,0 +1,99 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Dump of the code generated by #[widget(debug)], to debug the attribute without having to expand
+//! the whole crate.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{File, Ident, parse2};
+use syn::parse::{Error, Result};
+
+use super::gen_warning;
+
+/// Check if the #[widget] attribute has the debug argument.
+pub fn has_debug_argument(attributes: TokenStream) -> Result<bool> {
+    if attributes.is_empty() {
+        return Ok(false);
+    }
+    let argument: Ident = parse2(attributes)?;
+    if argument == "debug" {
+        Ok(true)
+    }
+    else {
+        Err(Error::new(argument.span(), format!("unknown #[widget] argument `{}`, expected `debug`", argument)))
+    }
+}
+
+/// Write the pretty-printed generated code in the target directory and return a warning
+/// indicating where it was written.
+pub fn dump(name: &Ident, tokens: &TokenStream) -> TokenStream {
+    let code =
+        match parse2::<File>(tokens.clone()) {
+            Ok(file) => prettyplease::unparse(&file),
+            Err(_) => tokens.to_string(),
+        };
+    let directory = dump_directory();
+    let path = directory.join(format!("{}.rs", name));
+    let note =
+        match fs::create_dir_all(&directory).and_then(|()| fs::write(&path, code)) {
+            Ok(()) => format!("the code generated by #[widget] for `{}` was written to {}", name, path.display()),
+            Err(error) => format!("cannot write the code generated by #[widget] for `{}` to {}: {}", name,
+                path.display(), error),
+        };
+    let warning = gen_warning(name.span(), &note);
+    let function = Ident::new(&format!("__relm_debug_{}", name), name.span());
+    quote! {
+        #[allow(dead_code, non_snake_case)]
+        fn #function() {
+            #warning
+        }
+    }
+}
+
+/// Get the directory `relm-derive` in the target directory of the crate being compiled.
+/// For a member of a workspace, the target directory is in the root of the workspace, not in the
+/// directory of the member.
+fn dump_directory() -> PathBuf {
+    let manifest_dir = env::var_os("CARGO_MANIFEST_DIR").map(PathBuf::from).unwrap_or_default();
+    let target_dir = env::var_os("CARGO_TARGET_DIR")
+        .map(|dir| manifest_dir.join(dir))
+        .unwrap_or_else(|| workspace_root(&manifest_dir).join("target"));
+    target_dir.join("relm-derive")
+}
+
+/// Get the outermost directory containing `manifest_dir` whose Cargo.toml declares a workspace, or
+/// `manifest_dir` itself if it is not part of a workspace.
+fn workspace_root(manifest_dir: &Path) -> PathBuf {
+    manifest_dir.ancestors()
+        .filter(|dir| {
+            fs::read_to_string(dir.join("Cargo.toml"))
+                .map(|manifest| manifest.lines().any(|line| line.trim() == "[workspace]"))
+                .unwrap_or(false)
+        })
+        .last()
+        .unwrap_or(manifest_dir)
+        .to_path_buf()
+}
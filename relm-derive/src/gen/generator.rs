@@ -31,25 +31,35 @@ use syn::{
     Type,
     TypePath,
     parse,
+    parse_str,
 };
 use syn::fold::Fold;
+use syn::visit::Visit;
 use syn::spanned::Spanned;
 
+use super::adder::gen_toggle_style_class;
 use super::parser::{
+    GENERATED_NAME_PREFIX,
     Event,
     GtkWidget,
     RelmWidget,
+    SharedValues,
     Widget,
 };
-use super::parser::EventValue::{CurrentWidget, ForeignWidget, NoEventValue};
+use super::parser::EventValue::{CurrentWidget, ForeignWidget};
 use super::parser::EventValueReturn::{CallReturn, Return, WithoutReturn};
 use super::parser::EitherWidget::{Gtk, Relm};
-use super::transformer::Transformer;
-use super::{Driver, MODEL_IDENT};
+use super::transformer::{EventTransformer, Transformer, mirror_ident, weak_widget_ident};
+use super::walker::EventSelfVisitor;
+use super::{Driver, MODEL_IDENT, gen_warning};
 
 use self::WidgetType::*;
 use self::WithParentheses::{WithParens, WithoutParens};
 
+/// The construct properties for which the GTK builders have no method, because they are
+/// write-only, like the group of a RadioButton: they are set right after the widget is built.
+const PROPERTIES_WITHOUT_BUILDER_METHOD: &[&str] = &["group"];
+
 #[derive(PartialEq)]
 enum WithParentheses {
     WithParens,
@@ -71,8 +81,53 @@ pub struct Gen {
 }
 
 pub fn gen(name: &Ident, widgets: &[Widget], driver: &mut Driver) -> Gen {
-    let mut generator = Generator::new(driver);
-    let mut widget_tokens = quote! {};
+    let builder =
+        if let Some(ref glade_file) = driver.glade_file {
+            quote_spanned! { name.span() =>
+                let __relm_builder = ::gtk::Builder::from_string(include_str!(#glade_file));
+            }
+        }
+        else {
+            quote! {}
+        };
+    let bindings =
+        if driver.has_bindings {
+            quote_spanned! { name.span() =>
+                let __relm_bindings = ::relm::ModelBindings::new();
+            }
+        }
+        else {
+            quote! {}
+        };
+    let mirrors =
+        if driver.mirrored_attributes.is_empty() {
+            quote! {}
+        }
+        else {
+            let model_ident = Ident::new(MODEL_IDENT, Span::call_site());
+            let mirrors = driver.mirrored_attributes.iter().map(|attribute| {
+                let mirror = mirror_ident(attribute);
+                let attribute_name = attribute.to_string();
+                quote_spanned! { attribute.span() =>
+                    let #mirror = __relm_mirrors.mirror(#attribute_name, &#model_ident,
+                        |model| ::std::clone::Clone::clone(&model.#attribute));
+                }
+            });
+            quote_spanned! { name.span() =>
+                let mut __relm_mirrors: ::relm::ModelMirrors<<Self as ::relm::Update>::Model> = ::relm::ModelMirrors::new();
+                #(#mirrors)*
+            }
+        };
+    let mut relm_widget_names = HashSet::new();
+    for widget in widgets {
+        collect_relm_widget_names(widget, &mut relm_widget_names);
+    }
+    let mut generator = Generator::new(driver, relm_widget_names);
+    let mut widget_tokens = quote! {
+        #builder
+        #bindings
+        #mirrors
+    };
     for (index, widget) in widgets.iter().enumerate() {
         // Only show the first item as the following could be non-widget like a gtk::Gesture.
         let tokens = generator.widget(widget, None, IsGtk, index == 0);
@@ -120,6 +175,36 @@ pub fn gen(name: &Ident, widgets: &[Widget], driver: &mut Driver) -> Gen {
             quote! {}
         };
 
+    let computed_field =
+        if driver.computed_methods.is_empty() {
+            quote! {}
+        }
+        else {
+            quote! {
+                computed: ::std::default::Default::default(),
+            }
+        };
+
+    let mirrors_field =
+        if driver.mirrored_attributes.is_empty() {
+            quote! {}
+        }
+        else {
+            quote! {
+                mirrors: __relm_mirrors,
+            }
+        };
+
+    let bindings_field =
+        if driver.has_bindings {
+            quote! {
+                bindings: __relm_bindings,
+            }
+        }
+        else {
+            quote! {}
+        };
+
     let view = quote_spanned! { name.span() =>
         #widget_tokens
 
@@ -139,6 +224,9 @@ pub fn gen(name: &Ident, widgets: &[Widget], driver: &mut Driver) -> Gen {
                 #(#component_names,)*
             },
             model: #model_ident,
+            #bindings_field
+            #computed_field
+            #mirrors_field
         }
     };
     let container_impl = gen_container_impl(&generator, &widgets[0], driver.generic_types.as_ref().expect("generic types"));
@@ -158,19 +246,21 @@ struct Generator<'a> {
     events: Vec<TokenStream>,
     properties: Vec<TokenStream>,
     relm_components: HashMap<Ident, Path>,
+    relm_widget_names: HashSet<Ident>,
     relm_widgets: HashMap<Ident, Path>,
     streams_to_save: HashSet<Ident>,
     widget_names: Vec<Ident>,
 }
 
 impl<'a> Generator<'a> {
-    fn new(driver: &'a mut Driver) -> Self {
+    fn new(driver: &'a mut Driver, relm_widget_names: HashSet<Ident>) -> Self {
         Generator {
             container_names: HashMap::new(),
             driver: Some(driver),
             events: vec![],
             properties: vec![],
             relm_components: HashMap::new(),
+            relm_widget_names,
             relm_widgets: HashMap::new(),
             streams_to_save: HashSet::new(),
             widget_names: vec![],
@@ -187,6 +277,11 @@ impl<'a> Generator<'a> {
                     ::gtk::prelude::ContainerExt::add(&#name, &#widget_name);
                 }
             }
+            else if let Some(ref slot) = widget.slot {
+                quote_spanned! { slot.span() =>
+                    ::gtk::prelude::ContainerExt::add(#name.containers.#slot(), &#widget_name);
+                }
+            }
             else {
                 quote! {
                     #name.add(&#widget_name);
@@ -210,25 +305,37 @@ impl<'a> Generator<'a> {
         }
     }
 
-    fn add_or_create_widget(&mut self, parent: Option<&Ident>, parent_widget_type: WidgetType, widget_name: &Ident,
-        widget_type_ident: &Path, init_parameters: &[Expr], is_container: bool) -> TokenStream
+    fn add_or_create_widget(&mut self, widget: &Widget, parent: Option<&Ident>, parent_widget_type: WidgetType)
+        -> TokenStream
     {
-        let init_parameters = gen_model_param(init_parameters, WithParens);
+        let widget_name = &widget.name;
+        let widget_type_ident = &widget.typ;
+        let is_container = widget.is_container;
+        let init_parameters = gen_model_param(&widget.init_parameters, WithParens);
         if let Some(parent) = parent {
+            let add_method =
+                if is_container {
+                    quote! { add_container }
+                }
+                else {
+                    quote! { add_widget }
+                };
             if parent_widget_type == IsGtk {
-                let add_method =
-                    if is_container {
-                        quote! { add_container }
-                    }
-                    else {
-                        quote! { add_widget }
-                    };
                 quote_spanned! { widget_name.span() =>
                     let #widget_name = {
                         ::relm::ContainerWidget::#add_method::<#widget_type_ident>(&#parent, #init_parameters)
                     };
                 }
             }
+            else if let Some(ref slot) = widget.slot {
+                // The slot is a container widget: add the relm widget directly in it.
+                quote_spanned! { slot.span() =>
+                    let #widget_name = {
+                        ::relm::ContainerWidget::#add_method::<#widget_type_ident>(#parent.containers.#slot(),
+                            #init_parameters)
+                    };
+                }
+            }
             else {
                 quote_spanned! { widget_name.span() =>
                     let #widget_name = {
@@ -262,40 +369,116 @@ impl<'a> Generator<'a> {
         }
     }
 
+    /// Set the initial values of the two-way bindings and connect their notify signals so that the
+    /// changes of the properties are written back into the model.
+    fn collect_bindings(&mut self, widget: &Widget) -> Vec<TokenStream> {
+        let widget_name = &widget.name;
+        let model_ident = Ident::new(MODEL_IDENT, Span::call_site());
+        let mut set_properties = vec![];
+        for (name, attribute) in &widget.bindings {
+            let property_name = name.to_string().replace('_', "-");
+            let attribute_name = attribute.to_string();
+            set_properties.push(quote_spanned! { name.span() =>
+                ::relm::ObjectExt::set_property(&#widget_name, #property_name, &#model_ident.#attribute);
+            });
+            self.events.push(quote_spanned! { name.span() => {
+                let bindings = __relm_bindings.clone();
+                ::relm::ObjectExt::connect_notify_local(&#widget_name, Some(#property_name), move |widget, _| {
+                    bindings.push(#attribute_name, ::relm::ObjectExt::property_value(widget, #property_name));
+                });
+            }});
+        }
+        set_properties
+    }
+
     fn collect_event(&mut self, widget_name: TokenStream, name: &Ident, event: &Event) {
         let event_ident = Ident::new(&format!("connect_{}", name), name.span());
         let event_params = &event.params;
-        let shared_values = gen_shared_values(&event.shared_values);
+        let captures = self.gen_event_captures(event);
         let metadata = gen_event_metadata(event);
         let connect =
             match event.value {
-                CurrentWidget(WithoutReturn(ref event_value)) => quote_spanned! { widget_name.span() => {
-                    #shared_values
-                    relm::connect!(relm, #widget_name, #event_ident(#(#event_params),*), #metadata #event_value);
-                }},
-                ForeignWidget(ref foreign_widget_name, WithoutReturn(ref event_value)) => quote! {{
-                    #shared_values
-                    relm::connect!(#widget_name, #event_ident(#(#event_params),*), #foreign_widget_name, #event_value);
-                }},
+                CurrentWidget(WithoutReturn(ref event_value)) => {
+                    let modifier_captures = gen_modifier_captures(event, quote! { relm.stream() });
+                    let event_value = gen_event_value(event, event_value, None);
+                    let metadata =
+                        if event.modifiers.prevent {
+                            quote! { return }
+                        }
+                        else {
+                            metadata
+                        };
+                    quote_spanned! { widget_name.span() => {
+                        #captures
+                        #modifier_captures
+                        relm::connect!(relm, #widget_name, #event_ident(#(#event_params),*), #metadata #event_value);
+                    }}
+                },
+                ForeignWidget(ref foreign_widget_name, ref event_value) => {
+                    let modifier_captures = gen_modifier_captures(event, quote! { &#foreign_widget_name.stream() });
+                    let event_value = gen_event_value(event, event_value, None);
+                    quote! {{
+                        #captures
+                        #modifier_captures
+                        relm::connect!(#widget_name, #event_ident(#(#event_params),*), #foreign_widget_name, #event_value);
+                    }}
+                },
                 CurrentWidget(Return(ref value)) => {
-                    let event_value = &value.0;
-                    let return_value = &value.1;
+                    let modifier_captures = gen_modifier_captures(event, quote! { relm.stream() });
+                    let event_value = gen_event_value(event, &value.0, Some(&value.1));
 
                     quote_spanned! { widget_name.span() => {
-                        #shared_values
-                        relm::connect!(relm, #widget_name, #event_ident(#(#event_params),*), return (#event_value, #return_value));
+                        #captures
+                        #modifier_captures
+                        relm::connect!(relm, #widget_name, #event_ident(#(#event_params),*), return #event_value);
+                    }}
+                },
+                CurrentWidget(CallReturn(ref func)) => {
+                    // The guard and the modifiers are not allowed with return: this is checked by the parser.
+                    let func = EventTransformer.fold_expr(func.clone());
+                    quote_spanned! { widget_name.span() => {
+                        #captures
+                        relm::connect!(relm, #widget_name, #event_ident(#(#event_params),*), #metadata #func);
                     }}
                 },
-                ForeignWidget(_, Return(_)) | ForeignWidget(_, CallReturn(_)) => unreachable!(),
-                CurrentWidget(CallReturn(ref func)) => quote_spanned! { widget_name.span() => {
-                    #shared_values
-                    relm::connect!(relm, #widget_name, #event_ident(#(#event_params),*), #metadata #func);
-                }},
-                NoEventValue => panic!("no event value"),
             };
         self.events.push(connect);
     }
 
+    /// Clone the copies of the model attributes and create the weak references to the widgets used
+    /// by the event handler, so that they can be moved in the closure.
+    fn gen_event_captures(&self, event: &Event) -> TokenStream {
+        let mut visitor = EventSelfVisitor::new();
+        for expr in event.exprs() {
+            visitor.visit_expr(expr);
+        }
+        let mirrors = visitor.model_attributes.iter().map(|attribute| {
+            let mirror = mirror_ident(attribute);
+            quote_spanned! { attribute.span() =>
+                let #mirror = ::std::rc::Rc::clone(&#mirror);
+            }
+        });
+        let weak_widgets = visitor.widgets.iter().map(|widget| {
+            let weak_widget = weak_widget_ident(widget);
+            let widget_expr =
+                if self.relm_widget_names.contains(widget) {
+                    quote! { #widget.widget() }
+                }
+                else {
+                    quote! { &#widget }
+                };
+            quote_spanned! { widget.span() =>
+                let #weak_widget = ::relm::ObjectExt::downgrade(#widget_expr);
+            }
+        });
+        let shared_values = event.shared_values.as_ref().map(gen_shared_values);
+        quote! {
+            #(#mirrors)*
+            #(#weak_widgets)*
+            #shared_values
+        }
+    }
+
     fn collect_events(&mut self, widget: &Widget, gtk_widget: &GtkWidget) {
         let widget_name = &widget.name;
         for (name, event) in &gtk_widget.events {
@@ -321,19 +504,31 @@ impl<'a> Generator<'a> {
                             (#(#event_params),*)
                         }
                     };
+                let captures = self.gen_event_captures(event);
                 let metadata = gen_event_metadata(event);
                 let connect =
                     match event.value {
-                        CurrentWidget(WithoutReturn(ref event_value)) => quote_spanned! { widget_name.span() => {
-                            relm::connect!(#widget_name@#event_ident #params, relm, #metadata #event_value);
-                        }},
-                        ForeignWidget(ref foreign_widget_name, WithoutReturn(ref event_value)) => quote! {{
-                            relm::connect!(#widget_name@#event_ident #params, #foreign_widget_name,
-                                     #metadata #event_value);
-                        }},
-                        CurrentWidget(Return(_)) | CurrentWidget(CallReturn(_)) | ForeignWidget(_, Return(_)) |
-                            ForeignWidget(_, CallReturn(_)) => unreachable!(),
-                        NoEventValue => panic!("no event value"),
+                        CurrentWidget(WithoutReturn(ref event_value)) => {
+                            let modifier_captures = gen_modifier_captures(event, quote! { relm.stream() });
+                            let event_value = gen_event_value(event, event_value, None);
+                            quote_spanned! { widget_name.span() => {
+                                #captures
+                                #modifier_captures
+                                relm::connect!(#widget_name@#event_ident #params, relm, #metadata #event_value);
+                            }}
+                        },
+                        ForeignWidget(ref foreign_widget_name, ref event_value) => {
+                            let modifier_captures = gen_modifier_captures(event, quote! { &#foreign_widget_name.stream() });
+                            let event_value = gen_event_value(event, event_value, None);
+                            quote! {{
+                                #captures
+                                #modifier_captures
+                                relm::connect!(#widget_name@#event_ident #params, #foreign_widget_name,
+                                         #metadata #event_value);
+                            }}
+                        },
+                        // Returning a value from a relm message event is an error reported by the parser.
+                        CurrentWidget(Return(_)) | CurrentWidget(CallReturn(_)) => unreachable!(),
                     };
                 self.events.push(connect);
             }
@@ -399,6 +594,7 @@ impl<'a> Generator<'a> {
 
         let construct_widget = gen_construct_widget(widget, gtk_widget);
         self.collect_events(widget, gtk_widget);
+        let bindings = self.collect_bindings(widget);
 
         let children: Vec<_> = widget.children.iter()
             .map(|child| self.widget(child, Some(widget_name), IsGtk, true))
@@ -411,6 +607,12 @@ impl<'a> Generator<'a> {
         let set_style_classes: Vec<_> = widget.style_classes.iter().map(|style_class|
             quote_spanned! { widget_name.span() => gtk::prelude::StyleContextExt::add_class(&#widget_name.style_context(), &#style_class); }
         ).collect();
+        let toggle_style_classes: Vec<_> = widget.dynamic_style_classes.iter().map(|(class, condition)| {
+            let mut remover = Transformer::new(MODEL_IDENT);
+            let condition = remover.fold_expr(condition.clone());
+            let toggle = gen_toggle_style_class(&quote! { #widget_name }, class, &quote! { #condition });
+            quote_spanned! { widget_name.span() => #toggle; }
+        }).collect();
 
         let show =
             if show {
@@ -421,13 +623,24 @@ impl<'a> Generator<'a> {
             else {
                 quote! { }
             };
+        // The widgets from a glade file already have their id as name.
+        let set_buildable_name =
+            if gtk_widget.builder_id.is_none() {
+                gen_set_buildable_name(widget, quote! { &#widget_name })
+            }
+            else {
+                quote! {}
+            };
 
         quote_spanned! { widget_name.span() =>
             let #widget_name: #struct_name = #construct_widget;
+            #set_buildable_name
             #(#properties)*
+            #(#bindings)*
             #(#children)*
             #add_child_or_show_all
             #(#set_style_classes)*
+            #(#toggle_style_classes)*
             #show
             #(#visible_properties)*
             #(#child_properties)*
@@ -458,13 +671,14 @@ impl<'a> Generator<'a> {
         self.properties.append(&mut properties);
         self.properties.append(&mut visible_properties);
 
-        let add_or_create_widget = self.add_or_create_widget(
-            parent, parent_widget_type, widget_name, widget_type_ident, &widget.init_parameters, widget.is_container);
+        let add_or_create_widget = self.add_or_create_widget(widget, parent, parent_widget_type);
         let child_properties = gen_set_child_prop_calls(widget, parent, parent_widget_type, IsRelm);
         let messages = self.messages(widget, relm_widget);
+        let set_buildable_name = gen_set_relm_buildable_name(widget, quote! { #widget_name.widget() });
 
         quote! {
             #add_or_create_widget
+            #set_buildable_name
             #messages
             #(#children)*
             #(#child_properties)*
@@ -488,16 +702,6 @@ impl<'a> Generator<'a> {
 
     fn set_container(&mut self, widget: &Widget, widget_name: &Ident, widget_type: &Path, is_relm_widget: bool) {
         if let Some(ref container_type) = widget.container_type {
-            if self.container_names.contains_key(container_type) {
-                let attribute =
-                    if let Some(ref typ) = *container_type {
-                        format!("#[container=\"{}\"]", typ)
-                    }
-                    else {
-                        "#[container]".to_string()
-                    };
-                panic!("Cannot use the {} attribute twice in the same widget", attribute);
-            }
             if is_relm_widget {
                 self.relm_components.insert(widget_name.clone(), widget_type.clone());
             }
@@ -516,19 +720,77 @@ impl<'a> Generator<'a> {
     }
 }
 
+fn collect_relm_widget_names(widget: &Widget, names: &mut HashSet<Ident>) {
+    if let Relm(_) = widget.widget {
+        names.insert(widget.name.clone());
+    }
+    for nested_view in widget.nested_views.values() {
+        collect_relm_widget_names(nested_view, names);
+    }
+    for child in &widget.children {
+        collect_relm_widget_names(child, names);
+    }
+}
+
 fn gen_construct_widget(widget: &Widget, gtk_widget: &GtkWidget) -> TokenStream {
     let struct_name = &widget.typ;
 
-    let mut parameters = vec![];
-    for (key, value) in gtk_widget.construct_properties.iter() {
-        let key = key.to_string().replace("_", "-");
+    if let Some(ref builder_id) = gtk_widget.builder_id {
+        let error = format!("cannot find object `{}` in the glade file", builder_id);
+        return quote_spanned! { widget.name.span() =>
+            ::gtk::prelude::BuilderExtManual::object::<#struct_name>(&__relm_builder, #builder_id)
+                .expect(#error)
+        };
+    }
+
+    let mut properties: Vec<_> = gtk_widget.construct_properties.iter().collect();
+    properties.sort_by_key(|&(key, _)| key.to_string());
+    let mut setters = vec![];
+    let mut post_build_setters = vec![];
+    for (key, value) in properties {
         let mut remover = Transformer::new(MODEL_IDENT);
         let value = remover.fold_expr(value.clone());
-        parameters.push(quote_spanned! { struct_name.span() =>
-            (#key, &#value as &dyn ::gtk::glib::value::ToValue)
-        });
+        let key_name = key.to_string();
+        if PROPERTIES_WITHOUT_BUILDER_METHOD.contains(&key_name.as_str()) {
+            let property_name = key_name.replace('_', "-");
+            post_build_setters.push(quote_spanned! { key.span() =>
+                ::relm::ObjectExt::set_property(&__relm_widget, #property_name, &#value);
+            });
+        }
+        else {
+            // Use the span of the property name so that a misspelled property points to the view.
+            setters.push(quote_spanned! { key.span() =>
+                .#key(#value)
+            });
+        }
     }
 
+    // Widgets without construct properties might not have a builder, like custom GObject types.
+    let construct =
+        if setters.is_empty() {
+            quote_spanned! { struct_name.span() =>
+                ::gtk::glib::object::Object::new::<#struct_name>(&[])
+            }
+        }
+        else {
+            quote_spanned! { struct_name.span() =>
+                #struct_name::builder()
+                    #(#setters)*
+                    .build()
+            }
+        };
+    let construct =
+        if post_build_setters.is_empty() {
+            construct
+        }
+        else {
+            quote_spanned! { struct_name.span() => {
+                let __relm_widget = #construct;
+                #(#post_build_setters)*
+                __relm_widget
+            }}
+        };
+
     if widget.init_parameters.is_empty() {
         quote_spanned!(struct_name.span() => {
             if !gtk::is_initialized_main_thread() {
@@ -539,9 +801,7 @@ fn gen_construct_widget(widget: &Widget, gtk_widget: &GtkWidget) -> TokenStream
                     panic!("GTK has not been initialized. Call `gtk::init` first.");
                 }
             }
-            let parameters = [#(#parameters),*];
-            // TODO: switch to builders.
-            ::gtk::glib::object::Object::new::<#struct_name>(&parameters)
+            #construct
         })
     }
     else {
@@ -552,6 +812,44 @@ fn gen_construct_widget(widget: &Widget, gtk_widget: &GtkWidget) -> TokenStream
     }
 }
 
+/// Set the name of the widget from the #[name] attribute as its buildable name, so that the tests
+/// can find it in the widget hierarchy.
+fn gen_set_buildable_name(widget: &Widget, widget_expr: TokenStream) -> TokenStream {
+    let name = widget.name.to_string();
+    if widget.save && !name.starts_with(GENERATED_NAME_PREFIX) {
+        quote_spanned! { widget.name.span() =>
+            ::gtk::prelude::BuildableExtManual::set_buildable_name(
+                ::relm::Cast::upcast_ref::<::gtk::Widget>(#widget_expr), #name);
+        }
+    }
+    else {
+        quote! {}
+    }
+}
+
+/// Same as gen_set_buildable_name() for the root of a relm widget, except that the buildable name
+/// is only set if this root has no name: the #[name] attribute of the root in the view of the relm
+/// widget takes precedence over the one of its parent, which is added as an extra name instead.
+fn gen_set_relm_buildable_name(widget: &Widget, widget_expr: TokenStream) -> TokenStream {
+    let name = widget.name.to_string();
+    if widget.save && !name.starts_with(GENERATED_NAME_PREFIX) {
+        quote_spanned! { widget.name.span() =>
+            {
+                let __relm_root = ::relm::Cast::upcast_ref::<::gtk::Widget>(#widget_expr);
+                if ::gtk::prelude::BuildableExtManual::buildable_name(__relm_root).is_none() {
+                    ::gtk::prelude::BuildableExtManual::set_buildable_name(__relm_root, #name);
+                }
+                else {
+                    ::relm::add_widget_name(__relm_root, #name);
+                }
+            }
+        }
+    }
+    else {
+        quote! {}
+    }
+}
+
 fn gen_event_metadata(event: &Event) -> TokenStream {
     if let CurrentWidget(CallReturn(_)) = event.value {
         quote! {
@@ -563,30 +861,80 @@ fn gen_event_metadata(event: &Event) -> TokenStream {
     }
 }
 
-fn gen_shared_values(shared_values: &[Ident]) -> TokenStream {
-    let model_ident = Ident::new(MODEL_IDENT, Span::call_site());
-    let fields = shared_values.iter()
-        .map(|ident| {
-             let typ =
-                 if ident == "relm" {
-                     quote_spanned! { ident.span() =>
-                         Relm<_>
-                     }
-                 }
-                 else {
-                     quote_spanned! { ident.span() =>
-                         ::std::rc::Rc<_>
-                     }
-                 };
-             quote! {
-                 let #ident: #typ = #model_ident.#ident.clone();
-             }
-        });
+/// Generate the variables used by the modifiers of the event handler, which sends its messages to
+/// `stream`.
+fn gen_modifier_captures(event: &Event, stream: TokenStream) -> TokenStream {
+    let once =
+        if event.modifiers.once {
+            quote! {
+                let __relm_once = ::std::cell::Cell::new(false);
+            }
+        }
+        else {
+            quote! {}
+        };
+    let debounce =
+        match event.modifiers.debounce {
+            Some(ref delay) => quote_spanned! { delay.span() =>
+                let __relm_debounce = ::relm::Debounce::new(#stream, #delay);
+            },
+            None => quote! {},
+        };
     quote! {
-        #(#fields)*
+        #once
+        #debounce
     }
 }
 
+/// Generate the value of the event handler (the message and, optionally, the value returned to the
+/// GTK+ callback), applying its guard and its modifiers.
+fn gen_event_value(event: &Event, message: &Expr, return_value: Option<&Expr>) -> TokenStream {
+    let message = EventTransformer.fold_expr(message.clone());
+    let return_value = return_value.map(|value| EventTransformer.fold_expr(value.clone()));
+    if !event.has_guard_or_modifiers() {
+        return match return_value {
+            Some(return_value) => quote! { (#message, #return_value) },
+            None => quote! { #message },
+        };
+    }
+    let guard =
+        match event.guard {
+            Some(ref guard) => EventTransformer.fold_expr(guard.clone()),
+            None => parse(quote! { true }.into()).expect("true"),
+        };
+    let once =
+        if event.modifiers.once {
+            quote! {
+                let msg = if msg.is_some() && __relm_once.replace(true) { None } else { msg };
+            }
+        }
+        else {
+            quote! {}
+        };
+    let debounce =
+        if event.modifiers.debounce.is_some() {
+            quote! {
+                let msg = __relm_debounce.emit(msg);
+            }
+        }
+        else {
+            quote! {}
+        };
+    let value =
+        match return_value {
+            Some(return_value) => quote! { (msg, #return_value) },
+            None if event.modifiers.prevent => quote! { (msg, ::gtk::Inhibit(__relm_guard)) },
+            None => quote! { msg },
+        };
+    quote! {{
+        let __relm_guard: bool = #guard;
+        let msg = if __relm_guard { ::relm::IntoOption::into_option(#message) } else { None };
+        #once
+        #debounce
+        #value
+    }}
+}
+
 fn gen_widget_ident(widget: &Widget) -> (TokenStream, Span) {
     match widget.widget {
         Gtk(ref gtk_widget) => {
@@ -685,9 +1033,6 @@ fn gen_container_impl(generator: &Generator, widget: &Widget, generic_types: &Ge
         quote! {
         }
     }
-    else if !generator.container_names.contains_key(&None) {
-        panic!("Use of #[container=\"name\"] attribute without the default #[container].");
-    }
     else {
         let mut container_type = None;
         for (ident, &(_, ref typ)) in &generator.container_names {
@@ -750,12 +1095,32 @@ fn gen_other_containers(generator: &Generator, widget_type: &TokenStream, widget
             types.push(typ);
         }
         let names = &names;
+        // Accessors for the named containers, used to fill them with #[slot="name"].
+        let mut slots = vec![];
+        let mut slot_fields = vec![];
+        let mut slot_types = vec![];
+        for (index, (parent_id, _)) in generator.container_names.iter().enumerate() {
+            if let Some(slot) = parent_id.as_ref().and_then(|parent_id| parse_str::<Ident>(parent_id).ok()) {
+                slots.push(slot);
+                slot_fields.push(&names[index]);
+                slot_types.push(&types[index]);
+            }
+        }
         (quote! {
             #[allow(dead_code)]
             #[derive(Clone)]
             pub struct #containers_ident {
                 #(#names: #types,)*
             }
+
+            #[allow(missing_docs)]
+            impl #containers_ident {
+                #(
+                    pub fn #slots(&self) -> &#slot_types {
+                        &self.#slot_fields
+                    }
+                )*
+            }
         }, quote! {
             #containers_ident
         }, quote! {
@@ -864,3 +1229,31 @@ pub fn gen_where_clause(generics: &Generics) -> TokenStream {
         }
     }*/
 }
+
+/// Clone the model attributes listed with the deprecated `with (...)` syntax.
+fn gen_shared_values(shared_values: &SharedValues) -> TokenStream {
+    let model_ident = Ident::new(MODEL_IDENT, Span::call_site());
+    let warning = gen_warning(shared_values.span,
+        "the `with (...)` syntax is deprecated: use `self.model.attribute` in the event handler instead");
+    let fields = shared_values.idents.iter()
+        .map(|ident| {
+            let typ =
+                if ident == "relm" {
+                    quote_spanned! { ident.span() =>
+                        ::relm::Relm<_>
+                    }
+                }
+                else {
+                    quote_spanned! { ident.span() =>
+                        ::std::rc::Rc<_>
+                    }
+                };
+            quote! {
+                let #ident: #typ = #model_ident.#ident.clone();
+            }
+        });
+    quote! {
+        #warning
+        #(#fields)*
+    }
+}
@@ -0,0 +1,128 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Minimal reader for GtkBuilder (glade) files: only the class and id of the objects are needed.
+
+/*
+ * Objects with these classes are not widgets, so they cannot be the root of the view.
+ */
+const NON_WIDGET_CLASSES: &[&str] = &[
+    "GtkAccelGroup",
+    "GtkAdjustment",
+    "GtkEntryBuffer",
+    "GtkEntryCompletion",
+    "GtkFileFilter",
+    "GtkListStore",
+    "GtkSizeGroup",
+    "GtkTextBuffer",
+    "GtkTextTagTable",
+    "GtkTreeModelFilter",
+    "GtkTreeModelSort",
+    "GtkTreeStore",
+];
+
+#[derive(Debug)]
+pub struct GladeObject {
+    pub class: String,
+    pub id: String,
+    pub top_level: bool,
+}
+
+impl GladeObject {
+    pub fn is_widget(&self) -> bool {
+        !NON_WIDGET_CLASSES.contains(&self.class.as_str())
+    }
+
+    /// Get the name of the type in the gtk crate, e.g. Window for GtkWindow.
+    pub fn gtk_class(&self) -> Option<&str> {
+        self.class.strip_prefix("Gtk").filter(|class| !class.is_empty())
+    }
+}
+
+/// Get the objects having an id, in the order in which they appear in the file.
+pub fn objects(xml: &str) -> Result<Vec<GladeObject>, String> {
+    let xml = remove_comments(xml);
+    let mut objects = vec![];
+    let mut depth = 0;
+    let mut rest = xml.as_str();
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        let end = rest.find('>').ok_or_else(|| "unterminated tag".to_string())?;
+        let tag = &rest[..end];
+        rest = &rest[end + 1..];
+        if tag.starts_with("/object") {
+            depth -= 1;
+        }
+        else if tag.starts_with("object") && tag[6..].starts_with(char::is_whitespace) {
+            let class = attribute(tag, "class")
+                .ok_or_else(|| "object without a class attribute".to_string())?;
+            if let Some(id) = attribute(tag, "id") {
+                objects.push(GladeObject {
+                    class,
+                    id,
+                    top_level: depth == 0,
+                });
+            }
+            if !tag.ends_with('/') {
+                depth += 1;
+            }
+        }
+    }
+    Ok(objects)
+}
+
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let mut rest = tag;
+    while let Some(index) = rest.find(name) {
+        let before = rest[..index].chars().last();
+        rest = &rest[index + name.len()..];
+        if before.map(char::is_whitespace) != Some(true) {
+            continue;
+        }
+        let value = rest.trim_start();
+        if let Some(value) = value.strip_prefix('=') {
+            let value = value.trim_start();
+            let quote = value.chars().next()?;
+            if quote != '"' && quote != '\'' {
+                return None;
+            }
+            let value = &value[1..];
+            let end = value.find(quote)?;
+            return Some(value[..end].to_string());
+        }
+    }
+    None
+}
+
+fn remove_comments(xml: &str) -> String {
+    let mut result = String::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find("<!--") {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        match rest.find("-->") {
+            Some(end) => rest = &rest[end + 3..],
+            None => rest = "",
+        }
+    }
+    result.push_str(rest);
+    result
+}
@@ -28,8 +28,14 @@
 pub(crate) mod parser;
 
 mod adder;
+mod computed;
+mod cycle;
+mod debug;
 mod generator;
+mod glade;
+mod params;
 mod transformer;
+mod update;
 mod walker;
 
 use std::collections::{HashMap, HashSet};
@@ -37,6 +43,8 @@ use std::collections::{HashMap, HashSet};
 use proc_macro2::{Span, TokenStream};
 use quote::{quote, quote_spanned};
 use syn::{
+    Block,
+    Expr,
     Generics,
     Ident,
     ImplItem,
@@ -50,21 +58,24 @@ use syn::{
     Signature,
     TypePath,
     parse,
+    parse2,
 };
 use syn::FnArg::{self, Typed};
 use syn::fold::Fold;
 use syn::ImplItem::{Const, Method, Verbatim};
 use syn::Item::{self, Impl};
-use syn::parse::Result;
+use syn::parse::{Error, Result};
 use syn::spanned::Spanned;
 use syn::Type;
 use syn::visit::Visit;
 
-use self::adder::{Adder, Message, Property};
+use self::adder::{Adder, Message, Property, PropertyKind};
+use self::computed::{ComputedDeps, ComputedInliner, ComputedMethod};
 pub use self::generator::gen_where_clause;
+pub use self::update::gen_update;
 use self::parser::EitherWidget::{Gtk, Relm};
-use self::parser::{Widget, WidgetList};
-use self::walker::ModelVariableVisitor;
+use self::parser::{GENERATED_NAME_PREFIX, Widget, WidgetList};
+use self::walker::{EventSelfVisitor, ModelVariableVisitor, SelfCallVisitor};
 
 const MODEL_IDENT: &str = "__relm_model";
 
@@ -73,10 +84,16 @@ type PropertyModelMap = HashMap<Ident, HashSet<Property>>;
 
 #[derive(Debug)]
 pub struct Driver {
+    computed_deps: ComputedDeps,
+    computed_methods: Vec<ComputedMethod>,
     data_method: Option<ImplItem>,
     generic_types: Option<Generics>,
+    glade_file: Option<String>,
+    has_bindings: bool,
     model_type: Option<ImplItem>,
     model_param_type: Option<ImplItem>,
+    model_params_struct: Option<TokenStream>,
+    mirrored_attributes: HashSet<Ident>,
     msg_model_map: Option<MsgModelMap>,
     msg_type: Option<ImplItem>,
     other_methods: Vec<ImplItem>,
@@ -109,10 +126,16 @@ struct View {
 impl Driver {
     fn new() -> Self {
         Driver {
+            computed_deps: HashMap::new(),
+            computed_methods: vec![],
             data_method: None,
             generic_types: None,
+            glade_file: None,
+            has_bindings: false,
             model_type: None,
             model_param_type: None,
+            model_params_struct: None,
+            mirrored_attributes: HashSet::new(),
             msg_model_map: None,
             msg_type: None,
             other_methods: vec![],
@@ -136,14 +159,15 @@ impl Driver {
         if let Method(ImplItemMethod { ref mut block, .. }) = *func {
             let msg_map = self.msg_model_map.as_ref().expect("update method");
             let property_map = self.properties_model_map.as_ref().expect("update method");
-            let mut adder = Adder::new(property_map, msg_map);
+            let mut adder = Adder::new(property_map, msg_map, &self.computed_deps, &self.mirrored_attributes);
             *block = adder.fold_block(block.clone());
         }
     }
 
     fn collect_bindings(&mut self, widget: &Widget, msg_model_map: &mut MsgModelMap, properties_model_map: &mut PropertyModelMap) {
-        get_properties_model_map(&widget, properties_model_map);
-        get_msg_model_map(&widget, msg_model_map);
+        get_properties_model_map(&widget, properties_model_map, &self.computed_deps);
+        get_msg_model_map(&widget, msg_model_map, &self.computed_deps);
+        get_event_model_attributes(widget, &mut self.mirrored_attributes);
         self.add_widgets(&widget, &properties_model_map);
 
         for nested_view in widget.nested_views.values() {
@@ -174,7 +198,7 @@ impl Driver {
         }
     }
 
-    fn create_struct(&self, typ: &Type, relm_widgets: &HashMap<Ident, Path>, relm_components: &HashMap<Ident, Path>, streams_to_save: &HashSet<Ident>, generics: &Generics) -> TokenStream {
+    fn create_struct(&self, typ: &Type, name: &Ident, relm_widgets: &HashMap<Ident, Path>, relm_components: &HashMap<Ident, Path>, streams_to_save: &HashSet<Ident>, generics: &Generics) -> TokenStream {
         let where_clause = gen_where_clause(generics);
         let root_widget_name = self.root_widget.as_ref().expect("root widget name");
         let widgets = self.widgets.iter()
@@ -182,9 +206,36 @@ impl Driver {
             .map(|(ident, tokens)| (ident.clone(), tokens));
         let (idents, types): (Vec<Ident>, Vec<_>) = widgets.unzip();
         let widget_model_type = self.widget_model_type.as_ref().expect("missing model method");
-        let components_name = Ident::new(&format!("__{}Components", get_name(&typ)), Span::call_site());
-        let widgets_name = Ident::new(&format!("__{}Widgets", get_name(&typ)), Span::call_site());
-        let streams_name = Ident::new(&format!("__{}Streams", get_name(&typ)), Span::call_site());
+        let components_name = Ident::new(&format!("__{}Components", name), Span::call_site());
+        let bindings =
+            if self.has_bindings {
+                quote! {
+                    bindings: ::relm::ModelBindings,
+                }
+            }
+            else {
+                quote! {}
+            };
+        let widgets_name = Ident::new(&format!("__{}Widgets", name), Span::call_site());
+        let streams_name = Ident::new(&format!("__{}Streams", name), Span::call_site());
+        let mirrors =
+            if self.mirrored_attributes.is_empty() {
+                quote! {}
+            }
+            else {
+                quote! {
+                    mirrors: ::relm::ModelMirrors<#widget_model_type>,
+                }
+            };
+        let (computed_field, computed_struct) =
+            if self.computed_methods.is_empty() {
+                (quote! {}, quote! {})
+            }
+            else {
+                let computed_name = Ident::new(&format!("__{}Computed", name), Span::call_site());
+                (quote! { computed: #computed_name, },
+                    computed::gen_computed_struct(&computed_name, &self.computed_methods))
+            };
         let components = {
             let components = relm_components.iter()
                 .map(|(ident, tokens)| (ident.clone(), tokens));
@@ -210,14 +261,26 @@ impl Driver {
         let widgets = {
             let relm_idents = relm_widgets.keys();
             let relm_types = relm_widgets.values();
-
             let component_idents = relm_components.keys();
+
+            // The widgets without a #[name] attribute might only be saved to keep them alive.
+            let allow_dead_code = |ident: &Ident| {
+                if ident.to_string().starts_with(GENERATED_NAME_PREFIX) {
+                    quote! { #[allow(dead_code)] }
+                }
+                else {
+                    quote! {}
+                }
+            };
+            let component_attributes = relm_components.keys().map(allow_dead_code);
+            let attributes = idents.iter().map(allow_dead_code);
+            let relm_attributes = relm_widgets.keys().map(allow_dead_code);
             quote! {
                 #[derive(Clone)]
                 pub struct #widgets_name {
-                    #(#component_idents: <#component_root_types as ::relm::Widget>::Root,)*
-                    #(#idents: #types,)*
-                    #(#relm_idents: #relm_types,)*
+                    #(#component_attributes #component_idents: <#component_root_types as ::relm::Widget>::Root,)*
+                    #(#attributes #idents: #types,)*
+                    #(#relm_attributes #relm_idents: #relm_types,)*
                 }
             }
         };
@@ -247,43 +310,55 @@ impl Driver {
                 components: #components_name,
                 widgets: #widgets_name,
                 model: #widget_model_type,
+                #bindings
+                #computed_field
+                #mirrors
             }
 
             #components
 
+            #computed_struct
+
             #streams
 
             #widgets
         }
     }
 
-    fn gen_widget(&mut self, input: TokenStream) -> TokenStream {
-        let mut ast: Item = parse(input.into()).expect("parse_item() in gen_widget()");
+    fn gen_widget(&mut self, input: TokenStream) -> Result<TokenStream> {
+        let mut ast: Item = parse2(input)?;
         if let Impl(ItemImpl { attrs, defaultness, unsafety, impl_token, generics, trait_, self_ty, items, brace_token }
                     ) = ast
         {
             self.generic_types = Some(generics.clone());
-            let name = get_name(&self_ty);
+            let name = get_name(&self_ty)?;
             let mut new_items = vec![];
             let mut update_items = vec![];
             for item in items {
                 let mut i = item.clone();
                 match item {
-                    Const(..) => panic!("Unexpected const item"),
+                    Const(item) => return Err(Error::new(item.span(), "unexpected const item in a #[widget] impl")),
                     ImplItem::Macro(mac) => self.view_macro = Some(mac.mac),
+                    Method(method) if computed::is_computed(&method) =>
+                        self.computed_methods.push(ComputedMethod::new(method)?),
                     Method(ImplItemMethod { sig, .. }) => {
                         match sig.ident.to_string().as_ref() {
                             "parent_id" => self.data_method = Some(i),
                             "root" => self.root_method = Some(i),
                             "model" => {
                                 self.widget_model_type = Some(get_return_type(sig));
+                                if let Method(ref mut method) = i {
+                                    if params::has_named_params(method) {
+                                        self.model_params_struct = Some(params::gen_model_params(&name, &generics, method)?);
+                                    }
+                                }
                                 add_model_param(&mut i, &mut self.model_param_type);
                                 update_items.push(i);
                             },
                             "subscriptions" => update_items.push(i),
                             "init_view" | "on_add" => new_items.push(i),
                             "update" => {
-                                self.widget_msg_type = Some(get_second_param_type(&sig));
+                                self.widget_msg_type = Some(get_second_param_type(&sig)?);
                                 self.update_method = Some(i)
                             },
                             _ => self.other_methods.push(i),
@@ -295,18 +370,26 @@ impl Driver {
                             "Model" => self.model_type = Some(i),
                             "ModelParam" => self.model_param_type = Some(i),
                             "Msg" => self.msg_type = Some(i),
-                            _ => panic!("Unexpected type item {:?}", typ.ident),
+                            _ => return Err(Error::new(typ.ident.span(),
+                                format!("unexpected type item `{}`, expected one of `Model`, `ModelParam`, `Msg` or `Root`",
+                                    typ.ident))),
                         }
                     },
-                    Verbatim(..) => panic!("Unexpected verbatim item"),
-                    _ => panic!("Unexpected item"),
+                    Verbatim(item) => return Err(Error::new(item.span(), "unexpected item in a #[widget] impl")),
+                    item => return Err(Error::new(item.span(), "unexpected item in a #[widget] impl")),
                 }
             }
-            let view =
-                match self.get_view(&name, &self_ty) {
-                    Ok(view) => view,
-                    Err(error) => return error.to_compile_error(),
-                };
+            if self.widget_model_type.is_none() {
+                return Err(Error::new(self_ty.span(), "missing `model` method in the #[widget] impl"));
+            }
+            if self.update_method.is_none() {
+                return Err(Error::new(self_ty.span(), "missing `update` method in the #[widget] impl"));
+            }
+            if self.view_macro.is_none() {
+                return Err(Error::new(self_ty.span(), "missing `view!` macro in the #[widget] impl"));
+            }
+            self.computed_deps = computed::get_computed_deps(&self.computed_methods);
+            let view = self.get_view(&name, &self_ty)?;
             if let Some(on_add) = gen_set_child_prop_calls(&view.widget) {
                 new_items.push(on_add);
             }
@@ -315,7 +398,9 @@ impl Driver {
             new_items.push(view.item);
             self.widgets.insert(self.root_widget.clone().expect("root widget"),
             self.root_widget_type.clone().expect("root widget type"));
-            let widget_struct = self.create_struct(&self_ty, &view.relm_widgets, &view.relm_components, &view.streams_to_save, &generics);
+            let widget_struct = self.create_struct(&self_ty, &name, &view.relm_widgets, &view.relm_components, &view.streams_to_save, &generics);
+            let computed_methods = self.computed_methods.drain(..).collect();
+            self.other_methods.extend(computed::gen_computed_methods(computed_methods, &self.computed_deps));
             new_items.push(self.get_root_type());
             if let Some(data_method) = self.get_data_method() {
                 new_items.push(data_method);
@@ -323,23 +408,25 @@ impl Driver {
             new_items.push(self.get_root());
             let other_methods = self.get_other_methods(&self_ty, &generics);
             let update_impl = self.update_impl(&self_ty, &generics, update_items);
-            let widget_test_impl = self.widget_test_impl(&self_ty, &generics);
+            let widget_test_impl = self.widget_test_impl(&self_ty, &name, &generics);
             let item = Impl(ItemImpl { attrs, defaultness, unsafety, generics, impl_token, trait_, self_ty, brace_token,
                 items: new_items });
             ast = item;
             let container_impl = view.container_impl;
-            quote! {
+            let model_params_struct = self.model_params_struct.take();
+            Ok(quote! {
                 #widget_struct
+                #model_params_struct
                 #ast
                 #container_impl
                 #update_impl
                 #widget_test_impl
 
                 #other_methods
-            }
+            })
         }
         else {
-            panic!("Expected impl");
+            Err(Error::new(ast.span(), "#[widget] can only be used on an impl block"))
         }
     }
 
@@ -424,17 +511,90 @@ impl Driver {
     fn get_update(&mut self) -> ImplItem {
         let mut func = self.update_method.take().expect("update method");
         self.add_set_property_to_method(&mut func);
+        if self.has_bindings {
+            if let Method(ImplItemMethod { ref mut block, .. }) = func {
+                // Write the values that could not be written when they came from the two-way
+                // bindings, and ignore the changes of the bound properties made by update().
+                let sync = quote! {
+                    self.sync_bindings();
+                    let __relm_bindings_guard = self.bindings.block();
+                };
+                let sync: Block = parse(quote! {{ #sync }}.into()).expect("bindings sync statements");
+                block.stmts.splice(0..0, sync.stmts);
+            }
+        }
         // TODO: consider gtk::main_quit() as return.
         func
     }
 
+    /// Move the body of update() to another method when there are mirrors, so that they can be
+    /// updated after it, even when it returns early or mutates the model in place.
+    fn wrap_update(&self, func: ImplItem) -> (ImplItem, Option<ImplItem>) {
+        if self.mirrored_attributes.is_empty() {
+            return (func, None);
+        }
+        match func {
+            Method(mut method) => {
+                let msg_type = get_second_param_type(&method.sig).expect("update msg type");
+                let span = method.sig.ident.span();
+                method.sig.ident = Ident::new("__relm_update", span);
+                let update = block_to_impl_item(quote_spanned! { span =>
+                    fn update(&mut self, event: #msg_type) {
+                        self.__relm_update(event);
+                        self.mirrors.update_all(&self.model);
+                    }
+                });
+                (update, Some(Method(method)))
+            },
+            func => (func, None),
+        }
+    }
+
+    /// Generate the methods writing the values coming from the two-way bindings into the model.
+    /// The assignments go through the adder so that they update the widgets like in update().
+    fn get_bindings_methods(&mut self) -> Vec<ImplItem> {
+        if !self.has_bindings {
+            return vec![];
+        }
+        let property_map = self.properties_model_map.as_ref().expect("update method");
+        let mut attributes: Vec<_> = property_map.iter()
+            .filter(|(_, properties)| properties.iter().any(|property| property.kind == PropertyKind::Binding))
+            .map(|(attribute, _)| attribute)
+            .collect();
+        attributes.sort();
+        let names = attributes.iter().map(|attribute| attribute.to_string());
+        let mut sync_bindings = block_to_impl_item(quote! {
+            fn sync_bindings(&mut self) {
+                let __relm_bindings_guard = self.bindings.block();
+                for (__relm_attribute, __relm_value) in self.bindings.take_writes() {
+                    match __relm_attribute {
+                        #(#names => {
+                            self.model.#attributes = __relm_value.get().expect("value of the bound property");
+                        },)*
+                        _ => (),
+                    }
+                }
+            }
+        });
+        self.add_set_property_to_method(&mut sync_bindings);
+        let model_bindings = block_to_impl_item(quote! {
+            fn model_bindings(&self) -> Option<&::relm::ModelBindings> {
+                Some(&self.bindings)
+            }
+        });
+        vec![model_bindings, sync_bindings]
+    }
+
     fn get_view(&mut self, name: &Ident, typ: &Type) -> Result<View> {
-        let WidgetList { mut widgets } = self
+        let WidgetList { glade_file, mut widgets } = self
             .view_macro
             .take()
             .expect("view_macro in impl_view()")
             .parse_body()?;
 
+        self.glade_file = glade_file;
+        check_containers(&widgets)?;
+
         self.widget_parent_id = widgets[0].parent_id.clone();
 
         let mut msg_model_map = HashMap::new();
@@ -445,12 +605,21 @@ impl Driver {
         for widget in &widgets {
             self.collect_bindings(widget, &mut msg_model_map, &mut properties_model_map);
         }
-
+        self.has_bindings = properties_model_map.values()
+            .flatten()
+            .any(|property| property.kind == PropertyKind::Binding);
+
+        let warnings = cycle::gen_event_cycle_warnings(&widgets, &properties_model_map, self.update_method.as_ref());
+        let mut inliner = ComputedInliner::new(&self.computed_deps);
+        for widget in &mut widgets {
+            inliner.inline_widget(widget);
+        }
         let generator::Gen { view, relm_widgets, relm_components, streams_to_save, container_impl } = generator::gen(name, &widgets, self);
         let model_ident = Ident::new(MODEL_IDENT, Span::call_site()); // TODO: maybe need to set Span here.
         let code = quote_spanned! { name.span() =>
             #[allow(unused_variables,clippy::all)] // Necessary to avoid warnings in case the parameters are unused.
             fn view(relm: &::relm::Relm<Self>, #model_ident: Self::Model) -> Self {
+                #(#warnings)*
                 #view
             }
         };
@@ -474,21 +643,31 @@ impl Driver {
         let msg = self.get_msg_type();
         let model_param = self.get_model_param_type();
         let update = self.get_update();
+        let (update, inner_update) = self.wrap_update(update);
+        let bindings_methods = self.get_bindings_methods();
         let model = self.get_model_type();
+        let inner_update_impl = inner_update.map(|inner_update| quote_spanned! { typ.span() =>
+            impl #generics #typ #where_clause {
+                #inner_update
+            }
+        });
         quote_spanned! { typ.span() =>
             impl #generics ::relm::Update for #typ #where_clause {
                 #msg
                 #model
                 #model_param
                 #update
+                #(#bindings_methods)*
                 #(#items)*
             }
+
+            #inner_update_impl
         }
     }
 
-    fn widget_test_impl(&self, typ: &Type, generics: &Generics) -> TokenStream {
-        let streams_name = Ident::new(&format!("__{}Streams", get_name(&typ)), Span::call_site());
-        let name = Ident::new(&format!("__{}Widgets", get_name(&typ)), Span::call_site());
+    fn widget_test_impl(&self, typ: &Type, name: &Ident, generics: &Generics) -> TokenStream {
+        let streams_name = Ident::new(&format!("__{}Streams", name), Span::call_site());
+        let name = Ident::new(&format!("__{}Widgets", name), Span::call_site());
         let where_clause = gen_where_clause(generics);
         quote_spanned! { typ.span() =>
             #[cfg(test)]
@@ -508,9 +687,33 @@ impl Driver {
     }
 }
 
-pub fn gen_widget(input: TokenStream) -> TokenStream {
+pub fn gen_widget(attributes: TokenStream, input: TokenStream) -> TokenStream {
+    let is_debug =
+        match debug::has_debug_argument(attributes) {
+            Ok(is_debug) => is_debug,
+            Err(error) => return error.to_compile_error(),
+        };
+    let name =
+        match parse2::<ItemImpl>(input.clone()) {
+            Ok(item) => get_name(&item.self_ty).ok(),
+            Err(_) => None,
+        };
     let mut driver = Driver::new();
-    driver.gen_widget(input)
+    match driver.gen_widget(input) {
+        Ok(tokens) => {
+            match name {
+                Some(ref name) if is_debug => {
+                    let note = debug::dump(name, &tokens);
+                    quote! {
+                        #tokens
+                        #note
+                    }
+                },
+                _ => tokens,
+            }
+        },
+        Err(error) => error.to_compile_error(),
+    }
 }
 
 fn add_model_param(model_fn: &mut ImplItem, model_param_type: &mut Option<ImplItem>) {
@@ -537,6 +740,52 @@ fn add_model_param(model_fn: &mut ImplItem, model_param_type: &mut Option<ImplIt
     }
 }
 
+/// Check that the #[container] attributes are used at most once each and that the default container
+/// is declared when named containers are used.
+fn check_containers(widgets: &[Widget]) -> Result<()> {
+    let mut containers = HashMap::new();
+    for widget in widgets {
+        collect_containers(widget, &mut containers)?;
+    }
+    if !containers.is_empty() && !containers.contains_key(&None) {
+        let span = *containers.values().next().expect("container span");
+        return Err(Error::new(span, "use of #[container=\"name\"] attribute without the default #[container]"));
+    }
+    Ok(())
+}
+
+fn collect_containers(widget: &Widget, containers: &mut HashMap<Option<String>, Span>) -> Result<()> {
+    if let Some(ref container_type) = widget.container_type {
+        if containers.insert(container_type.clone(), widget.typ.span()).is_some() {
+            let attribute =
+                if let Some(ref typ) = *container_type {
+                    format!("#[container=\"{}\"]", typ)
+                }
+                else {
+                    "#[container]".to_string()
+                };
+            return Err(Error::new(widget.typ.span(), format!("cannot use the {} attribute twice in the same widget", attribute)));
+        }
+    }
+    for nested_view in widget.nested_views.values() {
+        collect_containers(nested_view, containers)?;
+    }
+    for child in &widget.children {
+        collect_containers(child, containers)?;
+    }
+    Ok(())
+}
+
+/// Emit a compiler warning at `span`.
+/// Since proc-macros cannot emit warnings on stable, this is done by calling a deprecated function.
+fn gen_warning(span: Span, note: &str) -> TokenStream {
+    quote_spanned! { span => {
+        #[deprecated(note = #note)]
+        fn relm_warning() {}
+        relm_warning();
+    }}
+}
+
 fn block_to_impl_item(tokens: TokenStream) -> ImplItem {
     let implementation = quote! {
         impl Test {
@@ -550,28 +799,32 @@ fn block_to_impl_item(tokens: TokenStream) -> ImplItem {
     }
 }
 
-fn get_name(typ: &Type) -> Ident {
-    if let Type::Path(TypePath { ref path, .. }) = *typ {
-        let mut parts = vec![];
-        for segment in &path.segments {
-            parts.push(segment.ident.to_string());
+fn get_name(typ: &Type) -> Result<Ident> {
+    if let Type::Path(TypePath { qself: None, ref path }) = *typ {
+        if path.segments.len() == 1 {
+            return Ok(Ident::new(&path.segments[0].ident.to_string(), typ.span()));
         }
-        Ident::new(&parts.join("::"), typ.span())
     }
-    else {
-        panic!("Expected Path")
+    Err(Error::new(typ.span(), "expected the name of the widget, e.g. `Win`"))
+}
+
+/// Get the model attributes read by the event handlers of the widget.
+fn get_event_model_attributes(widget: &Widget, attributes: &mut HashSet<Ident>) {
+    for event in widget.events() {
+        let mut visitor = EventSelfVisitor::new();
+        for expr in event.exprs() {
+            visitor.visit_expr(expr);
+        }
+        attributes.extend(visitor.model_attributes);
     }
 }
 
-fn get_msg_model_map(widget: &Widget, map: &mut MsgModelMap) {
+fn get_msg_model_map(widget: &Widget, map: &mut MsgModelMap, computed_deps: &ComputedDeps) {
     match widget.widget {
         Gtk(_) => (),
         Relm(ref relm_widget) => {
             for (name, expr) in &relm_widget.messages {
-                let mut visitor = ModelVariableVisitor::new();
-                visitor.visit_expr(&expr);
-                let model_variables = visitor.idents;
-                for var in model_variables {
+                for var in model_variables(expr, computed_deps) {
                     let set = map.entry(var).or_insert_with(HashSet::new);
                     set.insert(Message {
                         expr: expr.clone(),
@@ -587,28 +840,63 @@ fn get_msg_model_map(widget: &Widget, map: &mut MsgModelMap) {
 /*
  * The map maps model variable name to a vector of tuples (widget name, property name).
  */
-fn get_properties_model_map(widget: &Widget, map: &mut PropertyModelMap) {
+fn get_properties_model_map(widget: &Widget, map: &mut PropertyModelMap, computed_deps: &ComputedDeps) {
     match widget.widget {
-        Gtk(_) => get_map(widget, map, false),
-        Relm(_) => get_map(widget, map, true),
+        Gtk(_) => get_map(widget, map, false, computed_deps),
+        Relm(_) => get_map(widget, map, true, computed_deps),
     }
 }
 
-fn get_map(widget: &Widget, map: &mut PropertyModelMap, is_relm: bool) {
+fn get_map(widget: &Widget, map: &mut PropertyModelMap, is_relm: bool, computed_deps: &ComputedDeps) {
     for (name, expr) in &widget.properties {
-        let mut visitor = ModelVariableVisitor::new();
-        visitor.visit_expr(&expr);
-        let model_variables = visitor.idents;
-        for var in model_variables {
+        for var in model_variables(expr, computed_deps) {
             let set = map.entry(var).or_insert_with(HashSet::new);
             set.insert(Property {
                 expr: expr.clone(),
                 is_relm_widget: is_relm,
+                kind: PropertyKind::Setter,
                 name: name.clone(),
                 widget_name: widget.name.clone(),
             });
         }
     }
+    for (name, attribute) in &widget.bindings {
+        let set = map.entry(attribute.clone()).or_default();
+        set.insert(Property {
+            expr: parse(quote! { &self.model.#attribute }.into()).expect("binding expression"),
+            is_relm_widget: is_relm,
+            kind: PropertyKind::Binding,
+            name: name.clone(),
+            widget_name: widget.name.clone(),
+        });
+    }
+    for (class, condition) in &widget.dynamic_style_classes {
+        for var in model_variables(condition, computed_deps) {
+            let set = map.entry(var).or_default();
+            set.insert(Property {
+                expr: condition.clone(),
+                is_relm_widget: is_relm,
+                kind: PropertyKind::StyleClass(class.clone()),
+                name: Ident::new("style_class", class.span()),
+                widget_name: widget.name.clone(),
+            });
+        }
+    }
+}
+
+/// Get the model attributes used in `expr`, including those read by the #[computed] methods it calls.
+fn model_variables(expr: &Expr, computed_deps: &ComputedDeps) -> HashSet<Ident> {
+    let mut visitor = ModelVariableVisitor::new();
+    visitor.visit_expr(expr);
+    let mut variables: HashSet<_> = visitor.idents.into_iter().collect();
+    let mut visitor = SelfCallVisitor::new();
+    visitor.visit_expr(expr);
+    for method in visitor.idents {
+        if let Some(deps) = computed_deps.get(&method) {
+            variables.extend(deps.iter().cloned());
+        }
+    }
+    variables
 }
 
 fn get_return_type(sig: Signature) -> Type {
@@ -623,12 +911,12 @@ fn get_return_type(sig: Signature) -> Type {
     }
 }
 
-fn get_second_param_type(sig: &Signature) -> Type {
-    if let Typed(PatType { ref ty, .. }) = sig.inputs[1] {
-        *ty.clone()
+fn get_second_param_type(sig: &Signature) -> Result<Type> {
+    if let Some(Typed(PatType { ref ty, .. })) = sig.inputs.iter().nth(1) {
+        Ok(*ty.clone())
     }
     else {
-        panic!("Unexpected `(unknown)`, expecting Typed Type"/*, sig.decl.inputs[1]*/); // TODO
+        Err(Error::new(sig.span(), "the update method must have the signature `fn update(&mut self, event: Msg)`"))
     }
 }
 
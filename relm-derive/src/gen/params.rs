@@ -0,0 +1,291 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Named model parameters: when the model() method takes several parameters or parameters with a
+//! #[default(value)] attribute, a params struct with a builder is generated and used as the
+//! ModelParam, so that the parent widget can set them by name in the view! macro.
+
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::{
+    Expr,
+    FnArg,
+    Generics,
+    Ident,
+    ImplItemMethod,
+    Pat,
+    PatIdent,
+    PatType,
+    Token,
+    Type,
+    TypeReference,
+    parse,
+};
+use syn::parse::{Error, Parser, Result};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+
+const DEFAULT_ATTRIBUTE: &str = "default";
+
+struct ModelParam {
+    default: Option<Expr>,
+    name: Ident,
+    pat: Pat,
+    ty: Type,
+}
+
+/// Check if the model() method declares named parameters.
+pub fn has_named_params(method: &ImplItemMethod) -> bool {
+    let mut params = method.sig.inputs.iter()
+        .skip_while(|input| is_relm_param(input))
+        .peekable();
+    let has_default = params.clone().any(|input| match *input {
+        FnArg::Typed(PatType { ref attrs, .. }) => attrs.iter().any(|attr| attr.path.is_ident(DEFAULT_ATTRIBUTE)),
+        FnArg::Receiver(_) => false,
+    });
+    let _first = params.next();
+    has_default || params.peek().is_some()
+}
+
+/// Replace the named parameters of the model() method by a single params struct and return the
+/// definition of this struct and its builder.
+pub fn gen_model_params(name: &Ident, generics: &Generics, method: &mut ImplItemMethod) -> Result<TokenStream> {
+    if !generics.params.is_empty() {
+        return Err(Error::new(generics.span(), "named model parameters are not supported on generic widgets"));
+    }
+    let relm_param =
+        match method.sig.inputs.first() {
+            Some(input) if is_relm_param(input) => Some(input.clone()),
+            _ => None,
+        };
+    let mut params = vec![];
+    for input in method.sig.inputs.iter().skip(relm_param.iter().count()) {
+        params.push(model_param(input)?);
+    }
+
+    let params_name = Ident::new(&format!("{}Params", name), name.span());
+    let builder_name = Ident::new(&format!("{}ParamsBuilder", name), name.span());
+    let names: Vec<_> = params.iter().map(|param| &param.name).collect();
+    let patterns = params.iter().map(|param| &param.pat);
+    let types: Vec<_> = params.iter().map(|param| &param.ty).collect();
+
+    // The required parameters are tracked in the type of the builder: their type parameter is a
+    // marker type until they are set, and build() only exists when they are all set.
+    let required: Vec<_> = params.iter().filter(|param| param.default.is_none()).collect();
+    let states: Vec<_> = (0..required.len())
+        .map(|index| Ident::new(&format!("__RelmParam{}", index), Span::call_site()))
+        .collect();
+    let markers: Vec<_> = required.iter()
+        .map(|param| Ident::new(&format!("{}Missing{}", params_name, camel_case(&param.name)), param.name.span()))
+        .collect();
+    let required_types: Vec<_> = required.iter().map(|param| &param.ty).collect();
+    let field_types = params.iter().map(|param| {
+        let ty = &param.ty;
+        match required.iter().position(|required| required.name == param.name) {
+            Some(index) => {
+                let state = &states[index];
+                quote! { #state }
+            },
+            None => quote! { Option<#ty> },
+        }
+    });
+    let setters = params.iter().map(|param| {
+        let name = &param.name;
+        let ty = &param.ty;
+        match required.iter().position(|required| required.name == param.name) {
+            Some(index) => {
+                let new_states = states.iter().enumerate().map(|(state_index, state)|
+                    if state_index == index {
+                        quote! { #ty }
+                    }
+                    else {
+                        quote! { #state }
+                    });
+                let fields = names.iter().map(|field| {
+                    if *field == name {
+                        quote! { #name }
+                    }
+                    else {
+                        quote! { #field: self.#field }
+                    }
+                });
+                quote! {
+                    pub fn #name(self, #name: #ty) -> #builder_name<#(#new_states),*> {
+                        #builder_name {
+                            #(#fields,)*
+                        }
+                    }
+                }
+            },
+            None => quote! {
+                pub fn #name(mut self, #name: #ty) -> Self {
+                    self.#name = Some(#name);
+                    self
+                }
+            },
+        }
+    });
+    let values = params.iter().map(|param| {
+        let name = &param.name;
+        match param.default {
+            Some(ref default) => quote_spanned! { default.span() =>
+                self.#name.unwrap_or_else(|| #default)
+            },
+            None => quote! {
+                self.#name
+            },
+        }
+    });
+    let initial_fields = params.iter().map(|param| {
+        let name = &param.name;
+        match required.iter().position(|required| required.name == param.name) {
+            Some(index) => {
+                let marker = &markers[index];
+                quote! { #name: #marker }
+            },
+            None => quote! { #name: None },
+        }
+    });
+    let default_impl =
+        if required.is_empty() {
+            quote! {
+                impl Default for #params_name {
+                    fn default() -> Self {
+                        <#params_name as ::relm::ModelParams>::builder().build()
+                    }
+                }
+            }
+        }
+        else {
+            quote! {}
+        };
+
+    let relm_param = relm_param.unwrap_or_else(|| parse(quote! { _: &::relm::Relm<Self> }.into()).expect("relm param"));
+    let params_ident = Ident::new("__relm_params", Span::call_site());
+    method.sig.inputs = parse_inputs(quote! { #relm_param, #params_ident: #params_name });
+    let destructure = quote! {
+        let #params_name { #(#patterns),* } = #params_ident;
+    };
+    method.block.stmts.insert(0, parse(destructure.into()).expect("params destructuring"));
+
+    let doc = format!("Parameters of the model of `{}`.", name);
+    let builder_doc = format!("Builder for `{}`.\n\n`build()` is only available once the parameters without a default value are set.", params_name);
+    let marker_docs = required.iter().map(|param| format!("Marker of the unset `{}` parameter of `{}`.", param.name, builder_name));
+    Ok(quote! {
+        #[doc = #doc]
+        pub struct #params_name {
+            #(pub #names: #types,)*
+        }
+
+        #default_impl
+
+        #(
+            #[doc = #marker_docs]
+            pub struct #markers;
+        )*
+
+        impl ::relm::ModelParams for #params_name {
+            type Builder = #builder_name<#(#markers),*>;
+
+            fn builder() -> Self::Builder {
+                #builder_name {
+                    #(#initial_fields,)*
+                }
+            }
+        }
+
+        #[doc = #builder_doc]
+        pub struct #builder_name<#(#states),*> {
+            #(#names: #field_types,)*
+        }
+
+        #[allow(missing_docs)]
+        impl<#(#states),*> #builder_name<#(#states),*> {
+            #(#setters)*
+        }
+
+        #[allow(missing_docs)]
+        impl #builder_name<#(#required_types),*> {
+            pub fn build(self) -> #params_name {
+                #params_name {
+                    #(#names: #values,)*
+                }
+            }
+        }
+    })
+}
+
+/// Convert a parameter name to CamelCase, e.g. `start_value` to `StartValue`.
+fn camel_case(name: &Ident) -> String {
+    name.to_string()
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+fn is_relm_param(input: &FnArg) -> bool {
+    if let FnArg::Typed(PatType { ref ty, .. }) = *input {
+        if let Type::Reference(TypeReference { ref elem, .. }) = **ty {
+            if let Type::Path(ref path) = **elem {
+                return path.path.segments.last().map(|segment| segment.ident == "Relm").unwrap_or(false);
+            }
+        }
+    }
+    false
+}
+
+fn model_param(input: &FnArg) -> Result<ModelParam> {
+    match *input {
+        FnArg::Typed(PatType { ref attrs, ref pat, ref ty, .. }) => {
+            let name =
+                match **pat {
+                    Pat::Ident(PatIdent { ref ident, .. }) => ident.clone(),
+                    _ => return Err(Error::new(pat.span(), "a named model parameter must be an identifier")),
+                };
+            let mut default = None;
+            for attr in attrs {
+                if attr.path.is_ident(DEFAULT_ATTRIBUTE) {
+                    default = Some(attr.parse_args()?);
+                }
+                else {
+                    return Err(Error::new(attr.span(), "expected #[default(value)] attribute on a model parameter"));
+                }
+            }
+            Ok(ModelParam {
+                default,
+                name,
+                pat: (**pat).clone(),
+                ty: (**ty).clone(),
+            })
+        },
+        FnArg::Receiver(ref receiver) => Err(Error::new(receiver.span(), "unexpected self parameter in model()")),
+    }
+}
+
+fn parse_inputs(tokens: TokenStream) -> Punctuated<FnArg, Token![,]> {
+    Punctuated::parse_terminated.parse2(tokens).expect("model inputs")
+}
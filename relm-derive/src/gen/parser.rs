@@ -21,15 +21,14 @@
 
 use std::collections::HashSet;
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::Read;
+use std::env;
+use std::fs;
 use std::iter::FromIterator;
+use std::path::PathBuf;
 use std::str::FromStr;
-use std::sync::Mutex;
 
-use lazy_static::lazy_static;
-use proc_macro2::{Span, TokenTree};
-use quote::{quote, ToTokens};
+use proc_macro2::Span;
+use quote::{quote, quote_spanned};
 use syn::{
     Expr,
     ExprMacro,
@@ -44,12 +43,17 @@ use syn::{
     parenthesized,
     parse,
     parse2,
+    parse_str,
     token,
     Token,
 };
 use syn::parse::{Error, Parse, ParseStream, Result};
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
+use syn::visit::Visit;
+
+use super::glade;
+use super::walker::{EventSelfVisitor, ModelVariableVisitor};
 
 use self::ChildItem::*;
 use self::EventValue::*;
@@ -60,10 +64,11 @@ use self::InitProperties::*;
 use self::WidgetPath::*;
 use self::SaveWidget::*;
 
-// TODO: switch to thread_local?
-lazy_static! {
-    static ref NAMES_INDEX: Mutex<HashMap<String, u32>> = Mutex::new(HashMap::new());
-}
+/*
+ * Prefix of the names of the widgets without a #[name] attribute.
+ * It starts with an underscore to avoid warnings when these widgets are not used.
+ */
+pub const GENERATED_NAME_PREFIX: &str = "__relm_field_";
 
 macro_rules! catch_return {
     ($expr:expr) => {
@@ -92,35 +97,94 @@ pub enum EventValueReturn {
 #[derive(Debug)]
 pub enum EventValue {
     CurrentWidget(EventValueReturn),
-    ForeignWidget(Ident, EventValueReturn),
-    NoEventValue,
+    ForeignWidget(Ident, Expr),
 }
 
 #[derive(Debug)]
 pub struct Event {
+    pub guard: Option<Expr>,
+    pub modifiers: EventModifiers,
     pub params: Vec<Pat>,
-    pub shared_values: Vec<Ident>,
-    pub use_self: bool,
+    pub shared_values: Option<SharedValues>,
     pub value: EventValue,
 }
 
+/// Model attributes cloned in the event handler with the deprecated `with (...)` syntax.
+#[derive(Debug)]
+pub struct SharedValues {
+    pub idents: Vec<Ident>,
+    pub span: Span,
+}
+
 impl Event {
-    fn new() -> Self {
-        Event {
-            params: vec![],
-            shared_values: vec![],
-            use_self: false,
-            value: NoEventValue,
+    /// Get the expressions evaluated when the event is emitted.
+    pub fn exprs(&self) -> Vec<&Expr> {
+        let mut exprs: Vec<_> = self.guard.iter().collect();
+        match self.value {
+            CurrentWidget(WithoutReturn(ref expr)) | CurrentWidget(CallReturn(ref expr)) | ForeignWidget(_, ref expr) =>
+                exprs.push(expr),
+            CurrentWidget(Return(ref values)) => exprs.extend(&[&values.0, &values.1]),
+        }
+        exprs
+    }
+
+    pub fn has_guard_or_modifiers(&self) -> bool {
+        self.guard.is_some() || self.modifiers.debounce.is_some() || self.modifiers.once || self.modifiers.prevent
+    }
+}
+
+/*
+ * Modifiers of an event handler, e.g. changed(entry).debounce(300).once => Msg::Search(entry.text()).
+ */
+#[derive(Debug, Default)]
+pub struct EventModifiers {
+    /// Delay in milliseconds: the message is only sent when no event was emitted during this delay.
+    pub debounce: Option<Expr>,
+    /// Only send the first message.
+    pub once: bool,
+    /// Return Inhibit(true) to stop the propagation of the event.
+    pub prevent: bool,
+}
+
+const EVENT_MODIFIERS: [&str; 3] = ["debounce", "once", "prevent"];
+
+impl Parse for EventModifiers {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut modifiers = EventModifiers::default();
+        while input.peek(Token![.]) {
+            let _dot: Token![.] = input.parse()?;
+            let name: Ident = input.parse()?;
+            match name.to_string().as_ref() {
+                "debounce" => {
+                    let content;
+                    let _parens = parenthesized!(content in input);
+                    modifiers.debounce = Some(content.parse()?);
+                },
+                "once" => modifiers.once = true,
+                "prevent" => modifiers.prevent = true,
+                _ => return Err(Error::new(name.span(),
+                    format!("unknown event modifier `{}`, expected `debounce(ms)`, `once` or `prevent`", name))),
+            }
         }
+        Ok(modifiers)
     }
 }
 
+/// Check if the next tokens are an event modifier, like `.once`, rather than a child event.
+fn peek_event_modifier(input: ParseStream) -> bool {
+    let input = input.fork();
+    input.parse::<Token![.]>().is_ok() &&
+        input.parse::<Ident>().map(|ident| EVENT_MODIFIERS.iter().any(|modifier| ident == modifier)).unwrap_or(false)
+}
+
 #[derive(Debug)]
 pub struct Widget {
+    pub bindings: HashMap<Ident, Ident>, // Map property name to model attribute.
     pub child_events: ChildEvents, // TODO: does it make sense for a relm widget?
     pub child_properties: ChildProperties, // TODO: does it make sense for a relm widget?
     pub children: Vec<Widget>,
     pub container_type: Option<Option<String>>, // TODO: Why two Options?
+    pub dynamic_style_classes: Vec<(LitStr, Expr)>, // Class names with the condition to have them.
     pub init_parameters: Vec<Expr>,
     pub is_container: bool,
     pub name: Ident,
@@ -128,12 +192,26 @@ pub struct Widget {
     pub parent_id: Option<String>,
     pub properties: HashMap<Ident, Expr>,
     pub save: bool,
+    pub slot: Option<Ident>, // Named container of the parent relm widget where this widget is added.
     pub typ: Path,
     pub widget: EitherWidget,
     pub style_classes: Vec<String>,
 }
 
 impl Widget {
+    /// Get the events connected on this widget (but not on its children).
+    pub fn events(&self) -> Vec<&Event> {
+        let mut events: Vec<_> = self.child_events.values().collect();
+        match self.widget {
+            Gtk(ref gtk_widget) => events.extend(gtk_widget.events.values()),
+            Relm(ref relm_widget) => {
+                events.extend(relm_widget.events.values().flatten());
+                events.extend(relm_widget.gtk_events.values());
+            },
+        }
+        events
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn new_gtk(widget: GtkWidget, typ: Path, init_parameters: Vec<Expr>, children: Vec<Widget>,
         properties: HashMap<Ident, Expr>, child_properties: ChildProperties, child_events: ChildEvents,
@@ -141,10 +219,12 @@ impl Widget {
     {
         let name = gen_widget_name(&typ);
         Widget {
+            bindings: HashMap::new(),
             child_events,
             child_properties,
             children,
             container_type: None,
+            dynamic_style_classes: vec![],
             init_parameters,
             is_container: false,
             name,
@@ -152,6 +232,7 @@ impl Widget {
             parent_id: None,
             properties,
             save: false,
+            slot: None,
             typ,
             widget: Gtk(widget),
             style_classes: vec![],
@@ -163,16 +244,16 @@ impl Widget {
         properties: HashMap<Ident, Expr>, child_properties: ChildProperties, child_events: ChildEvents,
         nested_views: HashMap<Ident, Widget>) -> Self
     {
-        let mut name = gen_widget_name(&typ);
         // Relm widgets are not used in the update() method; they are only saved to avoid dropping
-        // their channel too soon.
-        // So prepend an underscore to hide a warning.
-        name = Ident::new(&format!("_{}", name), name.span());
+        // their channel too soon: the underscore of the generated name hides a warning.
+        let name = gen_widget_name(&typ);
         Widget {
+            bindings: HashMap::new(),
             child_events,
             child_properties,
             children,
             container_type: None,
+            dynamic_style_classes: vec![],
             init_parameters,
             is_container: false,
             name,
@@ -180,6 +261,7 @@ impl Widget {
             parent_id: None,
             properties,
             save: false,
+            slot: None,
             typ,
             widget: Relm(widget),
             style_classes: vec![],
@@ -195,6 +277,7 @@ pub enum EitherWidget {
 
 #[derive(Debug)]
 pub struct GtkWidget {
+    pub builder_id: Option<String>,
     pub construct_properties: HashMap<Ident, Expr>,
     pub events: HashMap<Ident, Event>,
     pub relm_name: Option<Type>,
@@ -203,6 +286,7 @@ pub struct GtkWidget {
 impl GtkWidget {
     fn new() -> Self {
         GtkWidget {
+            builder_id: None,
             construct_properties: HashMap::new(),
             events: HashMap::new(),
             relm_name: None,
@@ -228,22 +312,25 @@ impl RelmWidget {
 }
 
 pub struct WidgetList {
+    pub glade_file: Option<String>,
     pub widgets: Vec<Widget>,
 }
 
 impl Parse for WidgetList {
     fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(Ident) && input.peek2(LitStr) {
+            Tag::parse(input, "glade")?;
+            return parse_glade(input);
+        }
+
         let lookahead = input.lookahead1();
         if lookahead.peek(LitStr) {
             let literal: LitStr = input.parse()?;
 
-            // TODO: also support glade file.
-            let mut file = File::open(literal.value()).expect("File::open() in parse()");
-            let mut file_content = String::new();
-            file.read_to_string(&mut file_content)
-                .expect("read_to_string() in parse()");
+            let file_content = fs::read_to_string(literal.value())
+                .map_err(|error| Error::new(literal.span(), format!("cannot read file `{}`: {}", literal.value(), error)))?;
             let tokens = proc_macro::TokenStream::from_str(&file_content)
-                .expect("convert string to TokenStream");
+                .map_err(|error| Error::new(literal.span(), format!("cannot parse file `{}`: {}", literal.value(), error)))?;
             let tokens = respan_with(tokens, literal.span().unwrap());
 
             syn::parse(tokens)
@@ -251,16 +338,141 @@ impl Parse for WidgetList {
             let mut widgets = vec![];
 
             while !input.is_empty() {
-                widgets.push(input.parse()?);
+                let widget = input.parse()?;
+                check_no_slot(&widget)?;
+                widgets.push(widget);
+            }
+            for (index, widget) in widgets.iter_mut().enumerate() {
+                name_widget(widget, None, &index.to_string());
             }
 
-            Ok(WidgetList { widgets })
+            Ok(WidgetList { glade_file: None, widgets })
         } else {
             Err(lookahead.error())
         }
     }
 }
 
+/*
+ * glade "window.glade" {
+ *     object_id.property: value,
+ *     object_id.property <=> self.model.attribute,
+ *     object_id.event(params) => Msg,
+ * }
+ */
+fn parse_glade(input: ParseStream) -> Result<WidgetList> {
+    let literal: LitStr = input.parse()?;
+    let path = manifest_path(&literal.value());
+    let file_content = fs::read_to_string(&path)
+        .map_err(|error| Error::new(literal.span(), format!("cannot read glade file `{}`: {}", literal.value(), error)))?;
+    let objects = glade::objects(&file_content)
+        .map_err(|error| Error::new(literal.span(), format!("invalid glade file `{}`: {}", literal.value(), error)))?;
+
+    let mut bindings = vec![];
+    if input.peek(token::Brace) {
+        let content;
+        let _brace = braced!(content in input);
+        bindings = Punctuated::<GladeBinding, Token![,]>::parse_terminated(&content)?.into_iter().collect();
+    }
+
+    let mut widgets = vec![];
+    let mut root_index = None;
+    for object in &objects {
+        let class = match object.gtk_class().and_then(|class| parse_str::<Ident>(class).ok()) {
+            Some(class) => Ident::new(&class.to_string(), literal.span()),
+            None => continue,
+        };
+        let name = match parse_str::<Ident>(&object.id.replace('-', "_")) {
+            Ok(name) => Ident::new(&name.to_string(), literal.span()),
+            Err(_) => continue,
+        };
+        let typ: Path = parse2(quote_spanned! { literal.span() =>
+            gtk::#class
+        })?;
+        if root_index.is_none() && object.top_level && object.is_widget() {
+            root_index = Some(widgets.len());
+        }
+        let mut gtk_widget = GtkWidget::new();
+        gtk_widget.builder_id = Some(object.id.clone());
+        let mut widget = Widget::new_gtk(gtk_widget, typ, vec![], vec![], HashMap::new(), HashMap::new(),
+            HashMap::new(), HashMap::new());
+        widget.name = name;
+        widget.save = true;
+        widgets.push(widget);
+    }
+
+    let root_index = root_index
+        .ok_or_else(|| Error::new(literal.span(), "no top-level widget found in glade file"))?;
+    let root = widgets.remove(root_index);
+    widgets.insert(0, root);
+
+    for binding in bindings {
+        let (id, name) = match binding {
+            GladeBinding::Binding(ref id, ref name, _) | GladeBinding::Event(ref id, ref name, _) |
+                GladeBinding::Property(ref id, ref name, _) =>
+                (id.clone(), name.clone()),
+        };
+        let widget = widgets.iter_mut()
+            .find(|widget| widget.name == id)
+            .ok_or_else(|| Error::new(id.span(), format!("no object with id `{}` in glade file `{}`", id, literal.value())))?;
+        match binding {
+            GladeBinding::Binding(_, _, model_attribute) => {
+                let _ = widget.bindings.insert(name, model_attribute);
+            },
+            GladeBinding::Property(_, _, value) => {
+                let _ = widget.properties.insert(name, value);
+            },
+            GladeBinding::Event(_, _, event) => {
+                if let Gtk(ref mut gtk_widget) = widget.widget {
+                    let _ = gtk_widget.events.insert(name, event);
+                }
+            },
+        }
+    }
+
+    Ok(WidgetList {
+        glade_file: Some(path.to_string_lossy().to_string()),
+        widgets,
+    })
+}
+
+enum GladeBinding {
+    Binding(Ident, Ident, Ident),
+    Event(Ident, Ident, Event),
+    Property(Ident, Ident, Expr),
+}
+
+impl Parse for GladeBinding {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let id: Ident = input.parse()?;
+        let _dot: Token![.] = input.parse()?;
+        let name: Ident = input.parse()?;
+        if input.peek(Token![:]) {
+            let _colon: Token![:] = input.parse()?;
+            let value = Value::parse(input)?;
+            Ok(GladeBinding::Property(id, name, value.value))
+        }
+        else if input.peek(Token![<=]) {
+            Ok(GladeBinding::Binding(id, name, parse_binding(input)?))
+        }
+        else {
+            let mut event = Event::parse(input)?;
+            if event.params.is_empty() {
+                event.params.push(wild_pat());
+            }
+            Ok(GladeBinding::Event(id, name, event))
+        }
+    }
+}
+
+/// Paths in the view! macro are relative to the root of the crate.
+fn manifest_path(path: &str) -> PathBuf {
+    match env::var("CARGO_MANIFEST_DIR") {
+        Ok(dir) => PathBuf::from(dir).join(path),
+        Err(_) => PathBuf::from(path),
+    }
+}
+
 enum InitProperties {
     ConstructProperties(HashMap<Ident, Expr>),
     InitParameters(Vec<Expr>),
@@ -329,6 +541,7 @@ impl Parse for InitPropertiesParser {
 }
 
 enum ChildItem {
+    Binding(Ident, Ident),
     ChildEvent(Ident, Ident, Event),
     ItemChildProperties(ChildProperties),
     ItemEvent(Ident, Event),
@@ -339,21 +552,6 @@ enum ChildItem {
     RelmMsgEvent(Ident, Event),
 }
 
-impl ChildItem {
-    fn unwrap_widget(self) -> Widget {
-        match self {
-            ChildEvent(_, _, _) => panic!("Expected widget, found child event"),
-            ItemEvent(_, _) => panic!("Expected widget, found event"),
-            ItemChildProperties(_) => panic!("Expected widget, found child properties"),
-            NestedView(_, _) => panic!("Expected widget, found nested view"),
-            Property(_, _) => panic!("Expected widget, found property"),
-            RelmMsg(_, _) => panic!("Expected widget, found relm msg"),
-            RelmMsgEvent(_, _) => panic!("Expected widget, found relm msg event"),
-            ChildWidget(widget) => widget,
-        }
-    }
-}
-
 struct AttributeValue {
     value: LitStr,
 }
@@ -381,7 +579,35 @@ impl Parse for NameValue {
     }
 }
 
+/*
+ * Style class toggled by a boolean expression:
+ * #[style_class(error = self.model.invalid, "destructive-action" = self.model.dangerous)]
+ */
+struct DynamicStyleClass {
+    class: LitStr,
+    condition: Expr,
+}
+
+impl Parse for DynamicStyleClass {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let class =
+            if input.peek(LitStr) {
+                input.parse()?
+            }
+            else {
+                let ident: Ident = input.parse()?;
+                LitStr::new(&ident.to_string(), ident.span())
+            };
+        let _equal: Token![=] = input.parse()?;
+        Ok(DynamicStyleClass {
+            class,
+            condition: input.parse()?,
+        })
+    }
+}
+
 struct Attribute {
+    dynamic_style_classes: Vec<(LitStr, Expr)>,
     name_values: HashMap<String, Option<LitStr>>, // TODO: Use Ident instead?
 }
 
@@ -390,24 +616,47 @@ impl Parse for Attribute {
         let _hash: Token![#] = input.parse()?;
         let content;
         let _bracket = bracketed!(content in input);
+        if content.peek(Ident) && content.peek2(token::Paren) {
+            let name: Ident = content.parse()?;
+            if name != "style_class" {
+                return Err(Error::new(name.span(), format!("unexpected arguments for the attribute `{}`", name)));
+            }
+            let classes;
+            let _parens = parenthesized!(classes in content);
+            let dynamic_style_classes = Punctuated::<DynamicStyleClass, Token![,]>::parse_terminated(&classes)?;
+            return Ok(Attribute {
+                dynamic_style_classes: dynamic_style_classes.into_iter()
+                    .map(|style_class| (style_class.class, style_class.condition))
+                    .collect(),
+                name_values: HashMap::new(),
+            });
+        }
         let name_values: Punctuated<NameValue, Token![,]> = content.parse_terminated(NameValue::parse)?;
+        for name_value in &name_values {
+            if name_value.name == "style_class" && name_value.value.is_none() {
+                return Err(Error::new(name_value.name.span(), "expected a class name, e.g. #[style_class=\"name\"]"));
+            }
+        }
         let name_values = name_values.into_iter()
             .map(|name_value| (name_value.name.to_string(), name_value.value.map(|value| value.value)))
             .collect();
 
         Ok(Attribute {
+            dynamic_style_classes: vec![],
             name_values,
         })
     }
 }
 
 struct Attributes {
+    dynamic_style_classes: Vec<(LitStr, Expr)>,
     name_values: HashMap<String, Option<LitStr>>,
     style_classes: HashSet<String>,
 }
 
 impl Parse for Attributes {
     fn parse(input: ParseStream) -> Result<Self> {
+        let mut dynamic_style_classes = vec![];
         let mut name_values = HashMap::new();
         let mut style_classes = HashSet::new();
         loop {
@@ -415,11 +664,9 @@ impl Parse for Attributes {
 
             if lookahead.peek(Token![#]) {
                 let attribute: Attribute = input.parse()?;
+                dynamic_style_classes.extend(attribute.dynamic_style_classes);
                 match attribute.name_values.get("style_class") {
-                    Some(Some(style_class)) => {
-                        style_classes.insert(style_class.value());
-                    },
-                    Some(None) => panic!("Invalid style_class specification"),
+                    Some(style_class) => style_classes.extend(style_class.as_ref().map(LitStr::value)),
                     None => name_values.extend(attribute.name_values)
                 };
             }
@@ -429,6 +676,7 @@ impl Parse for Attributes {
         }
 
         Ok(Attributes {
+            dynamic_style_classes,
             name_values,
             style_classes
         })
@@ -441,22 +689,6 @@ enum WidgetPath {
     GtkPath(Path),
 }
 
-impl WidgetPath {
-    fn get_relm_path(&self) -> &Path {
-        match *self {
-            RelmPath(ref ident) => ident,
-            GtkPath(_) => panic!("Expected ident"),
-        }
-    }
-
-    fn get_gtk_path(&self) -> &Path {
-        match *self {
-            RelmPath(_) => panic!("Expected path"),
-            GtkPath(ref path) => path,
-        }
-    }
-}
-
 struct WidgetPathParser {
     widget_path: WidgetPath,
 }
@@ -486,7 +718,7 @@ impl Parse for WidgetPathParser {
 }
 
 struct ChildWidgetParser {
-    widget: ChildItem,
+    widget: Widget,
     parent_id: Option<String>,
 }
 
@@ -504,21 +736,17 @@ impl ChildWidgetParser {
         let typ: WidgetPathParser = input.parse()?;
         let typ = typ.widget_path;
         let save = attributes.name_values.contains_key("name") || root == Save;
-        match typ {
-            RelmPath(_) => {
-                let relm_widget = RelmWidgetParser::parse(typ.get_relm_path().clone(), input)?.relm_widget;
-                Ok(adjust_widget_with_attributes(relm_widget, &attributes.name_values, &attributes.style_classes, save))
-            },
-            GtkPath(_) => {
-                let gtk_widget = GtkWidgetParser::parse(typ.get_gtk_path().clone(), input)?.gtk_widget;
-                Ok(adjust_widget_with_attributes(gtk_widget, &attributes.name_values, &attributes.style_classes, save))
-            },
-        }
+        let widget =
+            match typ {
+                RelmPath(path) => RelmWidgetParser::parse(path, input)?.relm_widget,
+                GtkPath(path) => GtkWidgetParser::parse(path, input)?.gtk_widget,
+            };
+        adjust_widget_with_attributes(widget, attributes, save)
     }
 }
 
 struct GtkWidgetParser {
-    gtk_widget: ChildItem,
+    gtk_widget: Widget,
 }
 
 impl GtkWidgetParser {
@@ -535,6 +763,7 @@ impl GtkWidgetParser {
         let mut child_events = HashMap::new();
         let mut child_properties = HashMap::new();
         let mut nested_views = HashMap::new();
+        let mut bindings = HashMap::new();
         for item in child_items.into_iter() {
             let item = item.item;
             match item {
@@ -547,10 +776,18 @@ impl GtkWidgetParser {
                     }
                 },
                 ItemEvent(ident, event) => { let _ = gtk_widget.events.insert(ident, event); },
-                ChildWidget(widget) => children.push(widget),
-                NestedView(ident, widget) => { let _ = nested_views.insert(ident, widget); },
+                ChildWidget(widget) => {
+                    check_no_slot(&widget)?;
+                    children.push(widget);
+                },
+                NestedView(ident, widget) => {
+                    check_no_slot(&widget)?;
+                    let _ = nested_views.insert(ident, widget);
+                },
                 Property(ident, value) => { let _ = properties.insert(ident, value.value); },
-                RelmMsg(_, _) | RelmMsgEvent(_, _) => panic!("Unexpected relm msg in gtk widget"),
+                Binding(ident, model_attribute) => { let _ = bindings.insert(ident, model_attribute); },
+                RelmMsg(ident, _) | RelmMsgEvent(ident, _) =>
+                    return Err(Error::new(ident.span(), "unexpected relm message in a gtk widget")),
             }
         }
         match init_properties {
@@ -558,9 +795,11 @@ impl GtkWidgetParser {
             InitParameters(init_params) => init_parameters = init_params,
             NoInitParameter => (),
         }
+        let mut widget = Widget::new_gtk(gtk_widget, typ, init_parameters, children, properties, child_properties,
+            child_events, nested_views);
+        widget.bindings = bindings;
         Ok(GtkWidgetParser {
-            gtk_widget: ChildWidget(Widget::new_gtk(gtk_widget, typ, init_parameters, children, properties,
-                            child_properties, child_events, nested_views)),
+            gtk_widget: widget,
         })
     }
 }
@@ -577,7 +816,7 @@ impl Parse for ChildRelmItem {
                 RelmPropertyOrEvent::parse(input)?.child_item
             }
             else {
-                ChildWidgetParser::parse(DontSave, input)?.widget
+                ChildWidget(ChildWidgetParser::parse(DontSave, input)?.widget)
             };
         Ok(ChildRelmItem {
             child_item,
@@ -586,7 +825,7 @@ impl Parse for ChildRelmItem {
 }
 
 struct RelmWidgetParser {
-    relm_widget: ChildItem,
+    relm_widget: Widget,
 }
 
 impl RelmWidgetParser {
@@ -596,7 +835,12 @@ impl RelmWidgetParser {
             if lookahead.peek(token::Paren) {
                 let content;
                 let _parens = parenthesized!(content in input);
-                Some(ExprList::parse(&content)?.exprs)
+                if content.peek(Ident) && content.peek2(Token![:]) && !content.peek2(Token![::]) {
+                    Some(vec![parse_named_params(&typ, &content)?])
+                }
+                else {
+                    Some(ExprList::parse(&content)?.exprs)
+                }
             }
             else {
                 None
@@ -622,6 +866,8 @@ impl RelmWidgetParser {
                         ChildEvent(event_name, child_name, event) => {
                             let _ = child_events.insert((child_name, event_name), event);
                         },
+                        Binding(ident, _) =>
+                            return Err(Error::new(ident.span(), "two-way bindings are only supported on gtk widgets")),
                         ChildWidget(widget) => children.push(widget),
                         ItemEvent(ident, event) => { let _ = relm_widget.gtk_events.insert(ident, event); },
                         ItemChildProperties(child_props) => {
@@ -638,13 +884,13 @@ impl RelmWidgetParser {
                         },
                     }
                 }
-                ChildWidget(Widget::new_relm(relm_widget, typ, init_parameters, children, properties,
-                    child_properties, child_events, nested_views))
+                Widget::new_relm(relm_widget, typ, init_parameters, children, properties, child_properties,
+                    child_events, nested_views)
             }
             else {
                 let init_parameters = init_parameters.unwrap_or_else(Vec::new);
-                ChildWidget(Widget::new_relm(RelmWidget::new(), typ, init_parameters, vec![], HashMap::new(),
-                    HashMap::new(), HashMap::new(), HashMap::new()))
+                Widget::new_relm(RelmWidget::new(), typ, init_parameters, vec![], HashMap::new(), HashMap::new(),
+                    HashMap::new(), HashMap::new())
             };
         Ok(RelmWidgetParser {
             relm_widget,
@@ -669,7 +915,7 @@ impl Parse for RelmPropertyOrEvent {
                     // Uppercase is a msg to send.
                     match result {
                         Property(ident, value) => RelmMsg(ident, value),
-                        _ => panic!("Expecting property"),
+                        _ => return Err(Error::new(ident.span(), "expected a value for the message")),
                     }
                 }
                 else {
@@ -677,7 +923,7 @@ impl Parse for RelmPropertyOrEvent {
                     result
                 }
             }
-            else if lookahead.peek(Token![.]) {
+            else if lookahead.peek(Token![.]) && !peek_event_modifier(input) {
                 let _colon: Token![.] = input.parse()?;
                 let event_name: Ident = input.parse()?;
                 let event = Event::parse(input)?;
@@ -687,6 +933,12 @@ impl Parse for RelmPropertyOrEvent {
                 let mut event = Event::parse(input)?;
                 if ident.to_string().chars().next().map(|char| char.is_lowercase()) == Some(false) {
                     // Uppercase is a msg.
+                    if let CurrentWidget(Return(_)) | CurrentWidget(CallReturn(_)) = event.value {
+                        return Err(Error::new(ident.span(), "cannot return a value from a relm message event"));
+                    }
+                    if event.modifiers.prevent {
+                        return Err(Error::new(ident.span(), "cannot use `.prevent` on a relm message event"));
+                    }
                     RelmMsgEvent(ident, event)
                 }
                 else {
@@ -711,6 +963,7 @@ struct GtkChildPropertyOrEvent {
  * First tokens:
  * * ident
  * * * :
+ * * * <=>
  * * * .
  * * * ( (in Event)
  * * * with
@@ -725,7 +978,10 @@ impl Parse for GtkChildPropertyOrEvent {
                 let _colon: Token![:] = input.parse()?;
                 ValueOrChildProperties::parse(input, &ident)?.child_item
             }
-            else if lookahead.peek(Token![.]) {
+            else if lookahead.peek(Token![<=]) {
+                Binding(ident, parse_binding(input)?)
+            }
+            else if lookahead.peek(Token![.]) && !peek_event_modifier(input) {
                 let _colon: Token![.] = input.parse()?;
                 let event_name: Ident = input.parse()?;
                 let mut event = Event::parse(input)?;
@@ -748,6 +1004,22 @@ impl Parse for GtkChildPropertyOrEvent {
     }
 }
 
+/*
+ * Parse the right side of a two-way binding, i.e. `<=> self.model.attribute`, and return the model
+ * attribute.
+ */
+fn parse_binding(input: ParseStream) -> Result<Ident> {
+    let _token: Token![<=] = input.parse()?;
+    let _token: Token![>] = input.parse()?;
+    let expr = Expr::parse(input)?;
+    let mut visitor = ModelVariableVisitor::new();
+    visitor.visit_expr(&expr);
+    match (&expr, visitor.idents.pop()) {
+        (Expr::Field(_), Some(attribute)) if visitor.idents.is_empty() => Ok(attribute),
+        _ => Err(Error::new(expr.span(), "expected a model attribute like `self.model.text` after `<=>`")),
+    }
+}
+
 struct ValueOrChildProperties {
     child_item: ChildItem,
 }
@@ -803,59 +1075,41 @@ impl Tag {
     }
 }
 
-struct SharedValues {
-    shared_values: Option<Vec<Ident>>,
-}
-
-impl Parse for SharedValues {
-    fn parse(input: ParseStream) -> Result<Self> {
-        let shared_values = catch_return! {{
-            Tag::parse(input, "with")?;
-            let content;
-            let _parens = parenthesized!(content in input);
-            IdentList::parse(&content)?.idents
-        }}.ok();
-        Ok(SharedValues {
-            shared_values,
-        })
+impl SharedValues {
+    fn parse(input: ParseStream) -> Result<Option<Self>> {
+        let span = input.span();
+        if Tag::parse(input, "with").is_err() {
+            return Ok(None);
+        }
+        let content;
+        let _parens = parenthesized!(content in input);
+        let idents: Punctuated<Ident, Token![,]> = content.parse_terminated(Ident::parse)?;
+        Ok(Some(SharedValues {
+            idents: idents.into_iter().collect(),
+            span,
+        }))
     }
 }
 
 enum IdentOrEventValue {
-    MessageIdent(EventValueReturn, bool),
-    MessageEventValue(Ident, EventValueReturn, bool),
-}
-
-fn expr_use_self(expr: &Expr) -> bool {
-    let mut tokens = quote! {};
-    expr.to_tokens(&mut tokens);
-    tokens.into_iter().any(|token| {
-        if let TokenTree::Ident(ident) = token {
-            return ident == "self";
-        }
-        false
-    })
+    MessageIdent(EventValueReturn),
+    MessageEventValue(Ident, EventValueReturn),
 }
 
 struct Value {
     value: Expr,
-    use_self: bool,
 }
 
 impl Parse for Value {
     fn parse(input: ParseStream) -> Result<Self> {
-        let expr = Expr::parse(input)?;
-        let use_self = expr_use_self(&expr);
         Ok(Value {
-            value: expr,
-            use_self,
+            value: Expr::parse(input)?,
         })
     }
 }
 
 struct EventValueParser {
     value_return: EventValueReturn,
-    use_self: bool,
 }
 
 impl Parse for EventValueParser {
@@ -866,7 +1120,6 @@ impl Parse for EventValueParser {
             let value = Value::parse(input)?;
             Ok(EventValueParser {
                 value_return: CallReturn(value.value),
-                use_self: value.use_self,
             })
         }
         else if lookahead.peek(token::Paren) {
@@ -877,14 +1130,12 @@ impl Parse for EventValueParser {
             let value2: Value = content.parse()?;
             Ok(EventValueParser {
                 value_return: Return(Box::new((value1.value, value2.value))),
-                use_self: value1.use_self || value2.use_self,
             })
         }
         else {
             let value = Value::parse(input)?;
             Ok(EventValueParser {
                 value_return: WithoutReturn(value.value),
-                use_self: value.use_self,
             })
         }
     }
@@ -907,13 +1158,13 @@ impl Parse for MessageSent {
             let _token: token::At = input.parse()?;
             let event_value = EventValueParser::parse(input)?;
             Ok(MessageSent {
-                ident_or_event_value: MessageEventValue(ident, event_value.value_return, event_value.use_self),
+                ident_or_event_value: MessageEventValue(ident, event_value.value_return),
             })
         }
         else {
             let event_value = EventValueParser::parse(input)?;
             Ok(MessageSent {
-                ident_or_event_value: MessageIdent(event_value.value_return, event_value.use_self),
+                ident_or_event_value: MessageIdent(event_value.value_return),
             })
         }
     }
@@ -932,19 +1183,6 @@ impl Parse for ExprList {
     }
 }
 
-struct IdentList {
-    idents: Vec<Ident>,
-}
-
-impl Parse for IdentList {
-    fn parse(input: ParseStream) -> Result<Self> {
-        let idents: Punctuated<Ident, Token![,]> = input.parse_terminated(Ident::parse)?;
-        Ok(IdentList {
-            idents: idents.into_iter().collect(),
-        })
-    }
-}
-
 impl Parse for Event {
     fn parse(input: ParseStream) -> Result<Self> {
         let content;
@@ -957,31 +1195,95 @@ impl Parse for Event {
             else {
                 None
             };
-        let shared_values = SharedValues::parse(input)?.shared_values;
-        let _token: Token![=>] = input.parse()?;
+        let modifiers = EventModifiers::parse(input)?;
+        let shared_values = SharedValues::parse(input)?;
+        let guard =
+            if input.peek(Token![if]) {
+                let _if: Token![if] = input.parse()?;
+                Some(input.parse()?)
+            }
+            else {
+                None
+            };
+        let arrow: Token![=>] = input.parse()?;
         let message_sent = MessageSent::parse(input)?.ident_or_event_value;
 
-        let mut event = Event::new();
-        if let Some(params) = params {
-            event.params = params.into_iter().collect();
-        }
-        if let Some(shared_values) = shared_values {
-            event.shared_values = shared_values;
-        }
-        match message_sent {
-            MessageIdent(event_value, use_self) => {
-                event.use_self = use_self;
-                event.value = CurrentWidget(event_value);
-            },
-            MessageEventValue(ident, event_value, use_self) => {
-                event.use_self = use_self;
-                event.value = ForeignWidget(ident, event_value);
-            },
-        }
+        let value =
+            match message_sent {
+                MessageIdent(event_value) => CurrentWidget(event_value),
+                MessageEventValue(ident, WithoutReturn(event_value)) => ForeignWidget(ident, event_value),
+                MessageEventValue(ident, _) =>
+                    return Err(Error::new(ident.span(), "cannot return a value from an event sending a message to another widget")),
+            };
+        let event = Event {
+            guard,
+            modifiers,
+            params: params.map(|params| params.into_iter().collect()).unwrap_or_default(),
+            shared_values,
+            value,
+        };
+        check_event_self_uses(&event)?;
+        check_event_modifiers(&event, arrow.spans[0])?;
         Ok(event)
     }
 }
 
+/// Check that self is only used to read model attributes and widgets in an event handler.
+fn check_event_self_uses(event: &Event) -> Result<()> {
+    let mut visitor = EventSelfVisitor::new();
+    for expr in event.exprs() {
+        visitor.visit_expr(expr);
+    }
+    match visitor.invalid_uses.first() {
+        Some(&span) => Err(Error::new(span, "only `self.model.attribute` and `self.widgets.name` can be used in an event handler")),
+        None => Ok(()),
+    }
+}
+
+fn check_event_modifiers(event: &Event, span: Span) -> Result<()> {
+    match event.value {
+        CurrentWidget(CallReturn(_)) if event.has_guard_or_modifiers() =>
+            Err(Error::new(span, "cannot use a guard or modifiers with `return`")),
+        CurrentWidget(Return(_)) if event.modifiers.prevent =>
+            Err(Error::new(span, "cannot use `.prevent` with an explicit return value")),
+        ForeignWidget(ref ident, _) if event.modifiers.prevent =>
+            Err(Error::new(ident.span(), "cannot use `.prevent` when sending a message to another widget")),
+        _ => Ok(()),
+    }
+}
+
+/// Parse the named model parameters, e.g. MyWidget(title: "x", max: 10), into a call to the
+/// builder of the params struct generated for the model() method of the child widget.
+fn parse_named_params(typ: &Path, input: ParseStream) -> Result<Expr> {
+    let params = Punctuated::<NamedParam, Token![,]>::parse_terminated(input)?;
+    let setters = params.iter().map(|NamedParam { name, value }| quote_spanned! { name.span() =>
+        .#name(#value)
+    });
+    let tokens = quote_spanned! { typ.span() =>
+        <<#typ as ::relm::Update>::ModelParam as ::relm::ModelParams>::builder()
+            #(#setters)*
+            .build()
+    };
+    parse2(tokens)
+}
+
+struct NamedParam {
+    name: Ident,
+    value: Expr,
+}
+
+impl Parse for NamedParam {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let name = input.parse()?;
+        let _colon: Token![:] = input.parse()?;
+        let value = input.parse()?;
+        Ok(NamedParam {
+            name,
+            value,
+        })
+    }
+}
+
 fn is_property_or_event(input: &ParseStream) -> bool {
     let input = input.fork();
     // Attributes start with # and qualified name for relm widget starts with $ .
@@ -998,12 +1300,16 @@ fn is_property_or_event(input: &ParseStream) -> bool {
             }
         }
     }
-    let _ident: Ident = input.parse().expect("should be an ident");
+    if input.parse::<Ident>().is_err() {
+        return false;
+    }
     if input.peek(token::Brace) {
         // Only a widget can have an ident followed by { .
         return false;
     }
-    if input.peek(Token![=>]) || input.peek(Token![.]) || input.peek(Token![:]) {
+    if input.peek(Token![=>]) || input.peek(Token![.]) || input.peek(Token![:]) || input.peek(Token![<=]) ||
+        input.peek(Token![if])
+    {
         // Only an event can contain => .
         return true;
     }
@@ -1018,12 +1324,14 @@ fn is_property_or_event(input: &ParseStream) -> bool {
         let _content;
         let _parens = parenthesized!(_content in input);
     }};
-    result.expect("parse parenthesis");
+    if result.is_err() {
+        return false;
+    }
     if input.peek(token::Brace) {
         // Only a widget can have an ident followed by { .
         return false;
     }
-    if input.peek(Token![=>]) || input.peek(Token![.]) || input.peek(Token![:]) {
+    if input.peek(Token![=>]) || input.peek(Token![.]) || input.peek(Token![:]) || input.peek(Token![if]) {
         // Only an event can contain => .
         return true;
     }
@@ -1053,7 +1361,7 @@ impl Parse for ChildGtkItem {
         }
         else {
             Ok(ChildGtkItem {
-                item: ChildWidgetParser::parse(DontSave, input)?.widget
+                item: ChildWidget(ChildWidgetParser::parse(DontSave, input)?.widget),
             })
         }
     }
@@ -1103,12 +1411,14 @@ impl Parse for Widget {
         let child_widget = ChildWidgetParser::parse(Save, input)?;
         let _token: Option<Token![,]> = input.parse().ok();
 
-        let mut widget = child_widget.widget.unwrap_widget();
+        let mut widget = child_widget.widget;
         widget.parent_id = child_widget.parent_id;
         Ok(widget)
     }
 }
 
+/// Generate a temporary name for a widget: it is replaced by name_widget() once the whole view is
+/// parsed, unless the widget has a #[name] attribute.
 fn gen_widget_name(path: &Path) -> Ident {
     let name = path_to_string(path);
     let name =
@@ -1118,10 +1428,32 @@ fn gen_widget_name(path: &Path) -> Ident {
         else {
             name.to_lowercase()
         };
-    let mut hashmap = NAMES_INDEX.lock().expect("lock() in gen_widget_name()");
-    let index = hashmap.entry(name.clone()).or_insert(0);
-    *index += 1;
-    Ident::new(&format!("{}{}", name, index), path.span())
+    Ident::new(&format!("{}{}", GENERATED_NAME_PREFIX, name), path.span())
+}
+
+/*
+ * Give a name depending on the position of the widget in the view to the widgets without a
+ * #[name] attribute, so that the generated code does not depend on the expansion order of the macros.
+ * For instance, the label in the second child of the window is named __relm_field_gtkwindow0_gtkbox1_gtklabel0.
+ */
+fn name_widget(widget: &mut Widget, parent: Option<&str>, position: &str) {
+    let name = widget.name.to_string();
+    if let Some(typ) = name.strip_prefix(GENERATED_NAME_PREFIX) {
+        let name =
+            match parent {
+                Some(parent) => format!("{}{}_{}{}", GENERATED_NAME_PREFIX, parent, typ, position),
+                None => format!("{}{}{}", GENERATED_NAME_PREFIX, typ, position),
+            };
+        widget.name = Ident::new(&name, widget.name.span());
+    }
+    let name = widget.name.to_string();
+    let parent = name.strip_prefix(GENERATED_NAME_PREFIX).unwrap_or(&name);
+    for (index, child) in widget.children.iter_mut().enumerate() {
+        name_widget(child, Some(parent), &index.to_string());
+    }
+    for (property, nested_view) in &mut widget.nested_views {
+        name_widget(nested_view, Some(parent), &format!("_{}", property));
+    }
 }
 
 fn path_to_string(path: &Path) -> String {
@@ -1132,32 +1464,42 @@ fn path_to_string(path: &Path) -> String {
     string
 }
 
-fn adjust_widget_with_attributes(mut widget: ChildItem, attributes: &HashMap<String, Option<LitStr>>, style_classes: &HashSet<String>, save: bool)
-    -> ChildWidgetParser
-{
-    let parent_id;
-    match widget {
-        ChildWidget(ref mut widget) => {
-            widget.save = save;
-            let container_type = attributes.get("container")
-                .map(|typ| typ.as_ref().map(|lit| lit.value()));
-            let name = attributes.get("name").and_then(|name| name.clone());
-            if let Some(name) = name {
-                widget.name = Ident::new(&name.value(), name.span());
-            }
-            // style_class attribute
-            for style_class in style_classes {
-                widget.style_classes.push((*style_class).clone());
-            }
-            widget.is_container = !widget.children.is_empty();
-            widget.container_type = container_type;
-            parent_id = attributes.get("parent").and_then(|opt_str| opt_str.as_ref().map(|lit| lit.value()));
-        },
-        _ => panic!("Expecting widget"),
+fn adjust_widget_with_attributes(mut widget: Widget, attributes: Attributes, save: bool) -> Result<ChildWidgetParser> {
+    let Attributes { dynamic_style_classes, name_values: attributes, style_classes } = attributes;
+    widget.save = save;
+    let container_type = attributes.get("container")
+        .map(|typ| typ.as_ref().map(|lit| lit.value()));
+    let name = attributes.get("name").and_then(|name| name.clone());
+    if let Some(name) = name {
+        widget.name = Ident::new(&name.value(), name.span());
+    }
+    // style_class attribute
+    widget.style_classes.extend(style_classes);
+    if let (Relm(_), Some((class, _))) = (&widget.widget, dynamic_style_classes.first()) {
+        return Err(Error::new(class.span(), "cannot toggle a style class of a relm widget"));
     }
-    ChildWidgetParser {
+    widget.dynamic_style_classes = dynamic_style_classes;
+    if let Some(slot) = attributes.get("slot") {
+        let slot = slot.as_ref()
+            .ok_or_else(|| Error::new(widget.typ.span(), "expected a slot name, e.g. #[slot=\"header\"]"))?;
+        let name: Ident = parse_str(&slot.value())
+            .map_err(|_| Error::new(slot.span(), format!("invalid slot name `{}`", slot.value())))?;
+        widget.slot = Some(Ident::new(&name.to_string(), slot.span()));
+    }
+    widget.is_container = !widget.children.is_empty();
+    widget.container_type = container_type;
+    let parent_id = attributes.get("parent").and_then(|opt_str| opt_str.as_ref().map(|lit| lit.value()));
+    Ok(ChildWidgetParser {
         widget,
         parent_id,
+    })
+}
+
+/// A slot is a named container of a relm widget, so it can only be filled in a relm widget.
+fn check_no_slot(widget: &Widget) -> Result<()> {
+    match widget.slot {
+        Some(ref slot) => Err(Error::new(slot.span(), "#[slot] can only be used on a child of a relm widget")),
+        None => Ok(()),
     }
 }
 
@@ -1183,3 +1525,89 @@ pub fn respan_with(tokens: proc_macro::TokenStream, span: proc_macro::Span) -> p
 pub fn dummy_ident(ident: &str) -> Ident {
     Ident::new(ident, Span::call_site())
 }
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_str;
+
+    use super::{Widget, WidgetList};
+
+    fn names(widget: &Widget, result: &mut Vec<String>) {
+        result.push(widget.name.to_string());
+        for child in &widget.children {
+            names(child, result);
+        }
+        let mut nested_views: Vec<_> = widget.nested_views.iter().collect();
+        nested_views.sort_by_key(|(property, _)| property.to_string());
+        for (_, nested_view) in nested_views {
+            names(nested_view, result);
+        }
+    }
+
+    fn view_names(view: &str) -> Vec<String> {
+        let list: WidgetList = parse_str(view).expect("parse view");
+        let mut result = vec![];
+        for widget in &list.widgets {
+            names(widget, &mut result);
+        }
+        result
+    }
+
+    const VIEW: &str = r#"
+        gtk::Window {
+            gtk::Box {
+                gtk::Label {
+                    text: "first",
+                },
+                gtk::Label {
+                    text: "second",
+                },
+                #[name="button"]
+                gtk::Button {
+                    gtk::Label {
+                    },
+                },
+            },
+            gtk::MenuButton {
+                popover: view! {
+                    gtk::Popover {
+                        gtk::Label {
+                        },
+                    }
+                },
+                gtk::Popover {
+                },
+            },
+        }
+    "#;
+
+    #[test]
+    fn name_widgets_by_position() {
+        assert_eq!(view_names(VIEW), [
+            "__relm_field_gtkwindow0",
+            "__relm_field_gtkwindow0_gtkbox0",
+            "__relm_field_gtkwindow0_gtkbox0_gtklabel0",
+            "__relm_field_gtkwindow0_gtkbox0_gtklabel1",
+            "button",
+            "__relm_field_button_gtklabel0",
+            "__relm_field_gtkwindow0_gtkmenubutton1",
+            "__relm_field_gtkwindow0_gtkmenubutton1_gtkpopover0",
+            "__relm_field_gtkwindow0_gtkmenubutton1_gtkpopover_popover",
+            "__relm_field_gtkwindow0_gtkmenubutton1_gtkpopover_popover_gtklabel0",
+        ]);
+    }
+
+    #[test]
+    fn name_widgets_uniquely() {
+        let mut names = view_names(VIEW);
+        let count = names.len();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), count);
+    }
+
+    #[test]
+    fn name_widgets_deterministically() {
+        assert_eq!(view_names(VIEW), view_names(VIEW));
+    }
+}
@@ -19,11 +19,12 @@
  * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
  */
 
-//! Transformer to transform the self.model by the actual model identifier.
+//! Transformers to transform the self.model by the actual model identifier and the uses of self in
+//! the event handlers.
 
 use std::sync::atomic::{AtomicUsize, Ordering};
 
-use quote::quote_spanned;
+use quote::{quote, quote_spanned};
 use proc_macro2::{Span, TokenStream};
 use syn::{
     Expr,
@@ -38,6 +39,7 @@ use syn::fold::{Fold, fold_expr};
 use syn::Member::Named;
 
 use super::parser::dummy_ident;
+use super::walker::{SelfMember, macro_exprs, self_member};
 
 thread_local! {
     static COUNTER: AtomicUsize = AtomicUsize::new(0);
@@ -106,4 +108,57 @@ impl Fold for Transformer {
         }
         fold_expr(self, expr)
     }
+
+    fn fold_macro(&mut self, mac: Macro) -> Macro {
+        fold_macro_exprs(self, mac)
+    }
+}
+
+/// Transformer to replace, in an event handler, the model attributes by their copies and the widgets
+/// by their weak references.
+pub struct EventTransformer;
+
+impl Fold for EventTransformer {
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        let tokens =
+            match self_member(&expr) {
+                Some(SelfMember::ModelAttribute(attribute)) => {
+                    let mirror = mirror_ident(attribute);
+                    quote_spanned! { expr.span() =>
+                        (*::std::cell::RefCell::borrow(&#mirror))
+                    }
+                },
+                Some(SelfMember::Widget(widget)) => {
+                    let weak_widget = weak_widget_ident(widget);
+                    quote_spanned! { expr.span() =>
+                        #weak_widget.upgrade().expect("widget used in an event handler was destroyed")
+                    }
+                },
+                None => return fold_expr(self, expr),
+            };
+        parse(tokens.into()).expect("self member replacement in event handler")
+    }
+
+    fn fold_macro(&mut self, mac: Macro) -> Macro {
+        fold_macro_exprs(self, mac)
+    }
+}
+
+/// Get the name of the variable holding the copy of the model `attribute` read by event handlers.
+pub fn mirror_ident(attribute: &Ident) -> Ident {
+    Ident::new(&format!("__relm_mirror_{}", attribute), attribute.span())
+}
+
+/// Get the name of the variable holding a weak reference to `widget` for event handlers.
+pub fn weak_widget_ident(widget: &Ident) -> Ident {
+    Ident::new(&format!("__relm_weak_{}", widget), widget.span())
+}
+
+/// Fold the arguments of a macro call when they are expressions separated by commas.
+pub fn fold_macro_exprs<F: Fold>(folder: &mut F, mut mac: Macro) -> Macro {
+    if let Some(exprs) = macro_exprs(&mac) {
+        let exprs = exprs.into_iter().map(|expr| folder.fold_expr(expr));
+        mac.tokens = quote! { #(#exprs),* };
+    }
+    mac
 }
@@ -0,0 +1,155 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Generation of a non-widget component from an `impl Update` block annotated with #[update]: the
+//! component struct, the associated types and the UpdateNew implementation.
+
+use std::collections::HashSet;
+
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::{
+    ImplItem,
+    ImplItemMethod,
+    Item,
+    ItemImpl,
+    parse2,
+};
+use syn::parse::{Error, Result};
+use syn::spanned::Spanned;
+
+use super::{
+    add_model_param,
+    block_to_impl_item,
+    gen_where_clause,
+    get_name,
+    get_return_type,
+    get_second_param_type,
+    params,
+};
+
+pub fn gen_update(input: TokenStream) -> TokenStream {
+    match gen(input) {
+        Ok(tokens) => tokens,
+        Err(error) => error.to_compile_error(),
+    }
+}
+
+fn gen(input: TokenStream) -> Result<TokenStream> {
+    let ast: Item = parse2(input)?;
+    let ItemImpl { attrs, generics, self_ty, items, .. } =
+        match ast {
+            Item::Impl(item) => item,
+            _ => return Err(Error::new(ast.span(), "#[update] can only be used on an impl block")),
+        };
+    let name = get_name(&self_ty)?;
+    let mut model_param_type = None;
+    let mut model_params_struct = None;
+    let mut model_type = None;
+    let mut msg_type = None;
+    let mut declared_types = HashSet::new();
+    let mut types = vec![];
+    let mut update_items = vec![];
+    let mut other_methods = vec![];
+    for item in items {
+        let mut i = item.clone();
+        match item {
+            ImplItem::Method(ImplItemMethod { sig, .. }) => {
+                match sig.ident.to_string().as_ref() {
+                    "model" => {
+                        model_type = Some(get_return_type(sig));
+                        if let ImplItem::Method(ref mut method) = i {
+                            if params::has_named_params(method) {
+                                model_params_struct = Some(params::gen_model_params(&name, &generics, method)?);
+                            }
+                        }
+                        add_model_param(&mut i, &mut model_param_type);
+                        update_items.push(i);
+                    },
+                    "update" => {
+                        msg_type = Some(get_second_param_type(&sig)?);
+                        update_items.push(i);
+                    },
+                    "subscriptions" => update_items.push(i),
+                    _ => other_methods.push(i),
+                }
+            },
+            ImplItem::Type(typ) => {
+                match typ.ident.to_string().as_ref() {
+                    "Model" | "ModelParam" | "Msg" => {
+                        declared_types.insert(typ.ident.to_string());
+                        types.push(i);
+                    },
+                    _ => return Err(Error::new(typ.ident.span(),
+                        format!("unexpected type item `{}`, expected one of `Model`, `ModelParam` or `Msg`", typ.ident))),
+                }
+            },
+            item => return Err(Error::new(item.span(), "unexpected item in an #[update] impl")),
+        }
+    }
+    let model_type = model_type.ok_or_else(|| Error::new(self_ty.span(), "missing `model` method in the #[update] impl"))?;
+    let msg_type = msg_type.ok_or_else(|| Error::new(self_ty.span(), "missing `update` method in the #[update] impl"))?;
+
+    let has_type = |ident: &str| declared_types.contains(ident);
+    if !has_type("Model") {
+        types.push(block_to_impl_item(quote! {
+            type Model = #model_type;
+        }));
+    }
+    if !has_type("ModelParam") {
+        types.push(model_param_type.unwrap_or_else(|| block_to_impl_item(quote! {
+            type ModelParam = ();
+        })));
+    }
+    if !has_type("Msg") {
+        types.push(block_to_impl_item(quote! {
+            type Msg = #msg_type;
+        }));
+    }
+
+    let where_clause = gen_where_clause(&generics);
+    Ok(quote_spanned! { self_ty.span() =>
+        #[allow(dead_code, missing_docs)]
+        pub struct #self_ty #where_clause {
+            model: #model_type,
+        }
+
+        #model_params_struct
+
+        #(#attrs)*
+        impl #generics ::relm::Update for #self_ty #where_clause {
+            #(#types)*
+            #(#update_items)*
+        }
+
+        impl #generics ::relm::UpdateNew for #self_ty #where_clause {
+            fn new(_: &::relm::Relm<Self>, model: #model_type) -> Self {
+                #name {
+                    model,
+                }
+            }
+        }
+
+        impl #generics #self_ty #where_clause {
+            #(#other_methods)*
+        }
+    })
+}
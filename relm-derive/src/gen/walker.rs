@@ -19,16 +19,35 @@
  * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
  */
 
-//! Visitor to get all the model attribute used in an expression.
+//! Visitors to get all the model attribute used in an expression, the methods called on self, the
+//! uses of self in the event handlers and the model attributes assigned when handling each message.
 
+use std::collections::{HashMap, HashSet};
+
+use proc_macro2::Span;
 use syn::{
+    Arm,
     Expr,
+    ExprAssign,
+    ExprAssignOp,
     ExprField,
+    ExprMethodCall,
     ExprPath,
     Ident,
+    Macro,
+    Pat,
+    PatIdent,
+    PatPath,
+    PatStruct,
+    PatTupleStruct,
+    Path,
 };
 use syn::Member::Named;
-use syn::visit::{Visit, visit_expr};
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::token::Comma;
+use syn::visit::{Visit, visit_arm, visit_expr};
 
 use super::parser::dummy_ident;
 
@@ -61,4 +80,196 @@ impl<'ast> Visit<'ast> for ModelVariableVisitor {
         }
         visit_expr(self, expr);
     }
+
+    fn visit_macro(&mut self, mac: &'ast Macro) {
+        for expr in macro_exprs(mac).iter().flatten() {
+            self.visit_expr(expr);
+        }
+    }
+}
+
+/// Collect the methods without arguments called on self, like `self.total()`.
+pub struct SelfCallVisitor {
+    pub idents: Vec<Ident>,
+}
+
+impl SelfCallVisitor {
+    pub fn new() -> Self {
+        SelfCallVisitor {
+            idents: vec![],
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for SelfCallVisitor {
+    fn visit_expr(&mut self, expr: &'ast Expr) {
+        if let Some(method) = self_call(expr) {
+            self.idents.push(method.clone());
+        }
+        visit_expr(self, expr);
+    }
+
+    fn visit_macro(&mut self, mac: &'ast Macro) {
+        for expr in macro_exprs(mac).iter().flatten() {
+            self.visit_expr(expr);
+        }
+    }
+}
+
+/// Collect the model attributes (`self.model.attribute`) and the widgets (`self.widgets.name`)
+/// used in an event handler, as well as the other uses of self, which are not allowed.
+pub struct EventSelfVisitor {
+    pub invalid_uses: Vec<Span>,
+    pub model_attributes: HashSet<Ident>,
+    pub widgets: HashSet<Ident>,
+}
+
+impl EventSelfVisitor {
+    pub fn new() -> Self {
+        EventSelfVisitor {
+            invalid_uses: vec![],
+            model_attributes: HashSet::new(),
+            widgets: HashSet::new(),
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for EventSelfVisitor {
+    fn visit_expr(&mut self, expr: &'ast Expr) {
+        match self_member(expr) {
+            Some(SelfMember::ModelAttribute(ident)) => {
+                self.model_attributes.insert(ident.clone());
+            },
+            Some(SelfMember::Widget(ident)) => {
+                self.widgets.insert(ident.clone());
+            },
+            None => {
+                if let Expr::Path(ExprPath { ref path, .. }) = *expr {
+                    if path.is_ident(&dummy_ident("self")) {
+                        self.invalid_uses.push(path.span());
+                    }
+                }
+                visit_expr(self, expr);
+            },
+        }
+    }
+
+    fn visit_macro(&mut self, mac: &'ast Macro) {
+        for expr in macro_exprs(mac).iter().flatten() {
+            self.visit_expr(expr);
+        }
+    }
+}
+
+pub enum SelfMember<'a> {
+    ModelAttribute(&'a Ident),
+    Widget(&'a Ident),
+}
+
+/// Check if `expr` is `self.model.attribute` or `self.widgets.name`.
+pub fn self_member(expr: &Expr) -> Option<SelfMember<'_>> {
+    if let Expr::Field(ExprField { ref base, member: Named(ref ident), .. }) = *expr {
+        if let Expr::Field(ExprField { base: ref self_expr, member: Named(ref self_member), .. }) = **base {
+            if let Expr::Path(ExprPath { ref path, .. }) = **self_expr {
+                if path.is_ident(&dummy_ident("self")) {
+                    if self_member == "model" {
+                        return Some(SelfMember::ModelAttribute(ident));
+                    }
+                    else if self_member == "widgets" {
+                        return Some(SelfMember::Widget(ident));
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Parse the arguments of a macro call like `format!("{}", self.model.text)`, when they are
+/// expressions separated by commas.
+pub fn macro_exprs(mac: &Macro) -> Option<Punctuated<Expr, Comma>> {
+    Punctuated::<Expr, Comma>::parse_terminated.parse2(mac.tokens.clone()).ok()
+}
+
+/// Get the name of the method if `expr` is a call like `self.total()`.
+pub fn self_call(expr: &Expr) -> Option<&Ident> {
+    if let Expr::MethodCall(ExprMethodCall { ref receiver, ref method, ref args, turbofish: None, .. }) = *expr {
+        if let Expr::Path(ExprPath { ref path, .. }) = **receiver {
+            if path.is_ident(&dummy_ident("self")) && args.is_empty() {
+                return Some(method);
+            }
+        }
+    }
+    None
+}
+
+/// Map each message variant matched in the update() method to the model attributes assigned in the
+/// corresponding match arm.
+pub struct ModelAssignVisitor {
+    pub assigned_attributes: HashMap<Ident, HashSet<Ident>>,
+}
+
+impl ModelAssignVisitor {
+    pub fn new() -> Self {
+        ModelAssignVisitor {
+            assigned_attributes: HashMap::new(),
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for ModelAssignVisitor {
+    fn visit_arm(&mut self, arm: &'ast Arm) {
+        let mut variants = vec![];
+        pattern_variants(&arm.pat, &mut variants);
+        if !variants.is_empty() {
+            let mut visitor = AssignedAttributes {
+                idents: HashSet::new(),
+            };
+            visitor.visit_expr(&arm.body);
+            for variant in variants {
+                self.assigned_attributes.entry(variant)
+                    .or_default()
+                    .extend(visitor.idents.iter().cloned());
+            }
+        }
+        visit_arm(self, arm);
+    }
+}
+
+struct AssignedAttributes {
+    idents: HashSet<Ident>,
+}
+
+impl<'ast> Visit<'ast> for AssignedAttributes {
+    fn visit_expr(&mut self, expr: &'ast Expr) {
+        if let Expr::Assign(ExprAssign { ref left, .. }) | Expr::AssignOp(ExprAssignOp { ref left, .. }) = *expr {
+            let mut visitor = ModelVariableVisitor::new();
+            visitor.visit_expr(left);
+            // Only the first attribute is assigned: in self.model.a[self.model.b], b is only read.
+            if let Some(ident) = visitor.idents.into_iter().next() {
+                self.idents.insert(ident);
+            }
+        }
+        visit_expr(self, expr);
+    }
+}
+
+fn pattern_variants(pat: &Pat, variants: &mut Vec<Ident>) {
+    match *pat {
+        // A unit variant imported with use Msg::*; is parsed as an identifier pattern.
+        Pat::Ident(PatIdent { ref ident, subpat: None, .. }) => variants.push(ident.clone()),
+        Pat::Or(ref pat_or) => {
+            for case in &pat_or.cases {
+                pattern_variants(case, variants);
+            }
+        },
+        Pat::Path(PatPath { ref path, .. }) | Pat::Struct(PatStruct { ref path, .. }) |
+            Pat::TupleStruct(PatTupleStruct { ref path, .. }) => variants.extend(path_variant(path)),
+        _ => (),
+    }
+}
+
+/// Get the last segment of a path, which is the variant name for Msg::Variant.
+pub fn path_variant(path: &Path) -> Option<Ident> {
+    path.segments.last().map(|segment| segment.ident.clone())
 }
@@ -28,6 +28,8 @@
 extern crate proc_macro;
 
 mod gen;
+mod msg;
+mod test;
 
 use quote::{quote, quote_spanned};
 use proc_macro2::TokenStream;
@@ -40,38 +42,72 @@ use syn::{
     TypeParam,
     parse,
 };
+use syn::parse::{Error, Result};
 use syn::spanned::Spanned;
 
-use gen::{gen_widget, gen_where_clause, parser::dummy_ident};
+use gen::{gen_update, gen_widget, gen_where_clause, parser::dummy_ident};
 
-#[proc_macro_derive(Msg)]
+#[proc_macro_derive(Msg, attributes(msg))]
 pub fn msg(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let ast: Item = parse(input).expect("msg > parse failed");
-    let gen = impl_msg(&ast, Ident::new("relm", ast.span()));
+    let ast: Item =
+        match parse(input) {
+            Ok(ast) => ast,
+            Err(error) => return error.to_compile_error().into(),
+        };
+    let gen =
+        match impl_msg(&ast, Ident::new("relm", ast.span())) {
+            Ok(gen) => gen,
+            Err(error) => error.to_compile_error(),
+        };
     gen.into()
 }
 
 #[proc_macro_attribute]
-pub fn widget(_attributes: proc_macro::TokenStream, input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let ast: Item = parse(input).expect("widget.parse failed");
+pub fn widget(attributes: proc_macro::TokenStream, input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let ast: Item =
+        match parse(input) {
+            Ok(ast) => ast,
+            Err(error) => return error.to_compile_error().into(),
+        };
     let tokens = quote! {
         #ast
     };
-    let expanded = gen_widget(tokens);
+    let expanded = gen_widget(attributes.into(), tokens);
     expanded.into()
 }
 
-fn impl_msg(ast: &Item, krate: Ident) -> TokenStream {
-    let display = derive_display_variant(ast, &krate);
-    let into_option = derive_into_option(ast, &krate);
+#[proc_macro_attribute]
+pub fn update(_attributes: proc_macro::TokenStream, input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    gen_update(input.into()).into()
+}
+
+/// Run the test on the GTK thread of relm-test instead of the thread of the test runner.
+/// This is meant to be used through its re-export, `#[relm_test::test]`.
+#[proc_macro_attribute]
+pub fn test(attributes: proc_macro::TokenStream, input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    match test::gen_test(attributes.into(), input.into()) {
+        Ok(gen) => gen.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn impl_msg(ast: &Item, krate: Ident) -> Result<TokenStream> {
+    let display = derive_display_variant(ast, &krate)?;
+    let into_option = derive_into_option(ast, &krate)?;
+    let helpers =
+        match *ast {
+            Item::Enum(ref enum_item) => msg::gen_msg_helpers(enum_item, &krate)?,
+            _ => quote! {},
+        };
 
-    quote! {
+    Ok(quote! {
         #display
         #into_option
-    }
+        #helpers
+    })
 }
 
-fn derive_display_variant(ast: &Item, krate: &Ident) -> TokenStream {
+fn derive_display_variant(ast: &Item, krate: &Ident) -> Result<TokenStream> {
     if let Item::Enum(ref enum_item) = *ast {
         let generics = &enum_item.generics;
         let name = &enum_item.ident;
@@ -82,7 +118,7 @@ fn derive_display_variant(ast: &Item, krate: &Ident) -> TokenStream {
 
         let variant_patterns = enum_item.variants.iter().map(|variant| {
             let doc_ident = dummy_ident("doc");
-            let attrs = variant.attrs.iter().filter(|attr| !attr.path.is_ident(&doc_ident));
+            let attrs = variant.attrs.iter().filter(|attr| !attr.path.is_ident(&doc_ident) && !attr.path.is_ident("msg"));
             let ident = &variant.ident;
             quote! {
                 #(#attrs)* #name::#ident { .. }
@@ -93,7 +129,7 @@ fn derive_display_variant(ast: &Item, krate: &Ident) -> TokenStream {
         });
         let where_clause = gen_where_clause(generics);
 
-        quote_spanned! { krate.span() =>
+        Ok(quote_spanned! { krate.span() =>
             impl #generics ::#krate::DisplayVariant for #typ #where_clause {
                 #[allow(unused_qualifications)]
                 fn display_variant(&self) -> &'static str {
@@ -102,14 +138,14 @@ fn derive_display_variant(ast: &Item, krate: &Ident) -> TokenStream {
                     }
                 }
             }
-        }
+        })
     }
     else {
-        panic!("Expected enum");
+        Err(Error::new(ast.span(), "#[derive(Msg)] can only be used on an enum"))
     }
 }
 
-fn derive_into_option(ast: &Item, krate: &Ident) -> TokenStream {
+fn derive_into_option(ast: &Item, krate: &Ident) -> Result<TokenStream> {
     if let Item::Enum(ref enum_item) = *ast {
         let generics = &enum_item.generics;
         let name = &enum_item.ident;
@@ -119,16 +155,16 @@ fn derive_into_option(ast: &Item, krate: &Ident) -> TokenStream {
         };
         let where_clause = gen_where_clause(generics);
 
-        quote_spanned! { krate.span() =>
+        Ok(quote_spanned! { krate.span() =>
             impl #generics ::#krate::IntoOption<#typ> for #typ #where_clause {
                 fn into_option(self) -> Option<#typ> {
                     Some(self)
                 }
             }
-        }
+        })
     }
     else {
-        panic!("Expecting enum");
+        Err(Error::new(ast.span(), "#[derive(Msg)] can only be used on an enum"))
     }
 }
 
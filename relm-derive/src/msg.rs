@@ -0,0 +1,420 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Optional helpers generated by #[derive(Msg)] when they are requested with #[msg(...)] on the
+//! enum: accessors, the list of variants, a Debug implementation showing the payloads and the serde
+//! traits.
+
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{
+    Attribute,
+    Field,
+    Fields,
+    GenericParam,
+    Ident,
+    ItemEnum,
+    Token,
+    Variant,
+    parse_quote,
+};
+use syn::parse::{Error, Result};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+
+use crate::gen::gen_where_clause;
+use crate::remove_generic_bounds;
+
+const MSG_ATTRIBUTE: &str = "msg";
+
+#[derive(Default)]
+pub struct MsgOptions {
+    accessors: bool,
+    debug: bool,
+    serde: bool,
+    variants: bool,
+}
+
+impl MsgOptions {
+    pub fn new(attrs: &[Attribute]) -> Result<Self> {
+        let mut options = MsgOptions::default();
+        for option in msg_arguments(attrs)? {
+            match option.to_string().as_ref() {
+                "accessors" => options.accessors = true,
+                "debug" => options.debug = true,
+                "serde" => options.serde = true,
+                "variants" => options.variants = true,
+                _ => return Err(Error::new(option.span(),
+                    format!("unknown #[msg] option `{}`, expected `accessors`, `debug`, `serde` or `variants`", option))),
+            }
+        }
+        Ok(options)
+    }
+}
+
+pub fn gen_msg_helpers(enum_item: &ItemEnum, krate: &Ident) -> Result<TokenStream> {
+    let options = MsgOptions::new(&enum_item.attrs)?;
+    let skipped_fields = enum_item.variants.iter()
+        .flat_map(|variant| variant.fields.iter())
+        .map(is_skipped)
+        .collect::<Result<Vec<_>>>()?;
+    if skipped_fields.contains(&true) && !options.debug {
+        return Err(Error::new(enum_item.ident.span(), "#[msg(skip)] requires the #[msg(debug)] option on the enum"));
+    }
+    let accessors =
+        if options.accessors {
+            gen_accessors(enum_item)
+        }
+        else {
+            quote! {}
+        };
+    let variants =
+        if options.variants {
+            gen_variants(enum_item)
+        }
+        else {
+            quote! {}
+        };
+    let debug =
+        if options.debug {
+            gen_debug(enum_item)?
+        }
+        else {
+            quote! {}
+        };
+    let serde =
+        if options.serde {
+            gen_serde(enum_item, krate)?
+        }
+        else {
+            quote! {}
+        };
+    Ok(quote! {
+        #accessors
+        #variants
+        #debug
+        #serde
+    })
+}
+
+fn gen_accessors(enum_item: &ItemEnum) -> TokenStream {
+    let name = &enum_item.ident;
+    let mut methods = vec![];
+    for variant in &enum_item.variants {
+        let ident = &variant.ident;
+        let attrs = variant_attrs(variant);
+        let snake_name = to_snake_case(&ident.to_string());
+        let is_method = Ident::new(&format!("is_{}", snake_name), ident.span());
+        let is_doc = format!("Check if the message is `{}`.", ident);
+        methods.push(quote! {
+            #(#attrs)*
+            #[doc = #is_doc]
+            pub fn #is_method(&self) -> bool {
+                matches!(*self, #name::#ident { .. })
+            }
+        });
+        if variant.fields.is_empty() {
+            continue;
+        }
+        let (pattern, bindings) = variant_pattern(name, variant, true);
+        let types = variant.fields.iter().map(|field| &field.ty);
+        let (typ, value) =
+            if bindings.len() == 1 {
+                (quote! { #(&#types)* }, quote! { #(#bindings)* })
+            }
+            else {
+                (quote! { (#(&#types),*) }, quote! { (#(#bindings),*) })
+            };
+        let as_method = Ident::new(&format!("as_{}", snake_name), ident.span());
+        let as_doc = format!("Get the payload of the message if it is `{}`.", ident);
+        methods.push(quote! {
+            #(#attrs)*
+            #[doc = #as_doc]
+            #[allow(unreachable_patterns)]
+            pub fn #as_method(&self) -> Option<#typ> {
+                match *self {
+                    #pattern => Some(#value),
+                    _ => None,
+                }
+            }
+        });
+    }
+    gen_inherent_impl(enum_item, methods)
+}
+
+fn gen_variants(enum_item: &ItemEnum) -> TokenStream {
+    let name = &enum_item.ident;
+    let attrs: Vec<_> = enum_item.variants.iter().map(variant_attrs).collect();
+    let idents: Vec<_> = enum_item.variants.iter().map(|variant| &variant.ident).collect();
+    let variant_names: Vec<_> = idents.iter().map(|ident| ident.to_string()).collect();
+    let methods = vec![quote! {
+        /// The names of the variants, in declaration order.
+        pub const VARIANTS: &'static [&'static str] = &[#(#(#attrs)* #variant_names),*];
+
+        /// Get the index of the variant of the message in VARIANTS.
+        pub fn variant_index(&self) -> usize {
+            // The variants disabled by a #[cfg] attribute are not in VARIANTS, so the index is
+            // found by name.
+            let variant_name =
+                match *self {
+                    #(#(#attrs)* #name::#idents { .. } => #variant_names,)*
+                };
+            Self::VARIANTS.iter()
+                .position(|name| *name == variant_name)
+                .expect("variant in VARIANTS")
+        }
+    }];
+    gen_inherent_impl(enum_item, methods)
+}
+
+fn gen_debug(enum_item: &ItemEnum) -> Result<TokenStream> {
+    let name = &enum_item.ident;
+    let mut arms = vec![];
+    for variant in &enum_item.variants {
+        let ident = &variant.ident;
+        let attrs = variant_attrs(variant);
+        let variant_name = ident.to_string();
+        let (pattern, bindings) = variant_pattern(name, variant, true);
+        let mut fields = vec![];
+        for (field, binding) in variant.fields.iter().zip(&bindings) {
+            let value =
+                if is_skipped(field)? {
+                    quote! { &format_args!("_") }
+                }
+                else {
+                    quote! { #binding }
+                };
+            match field.ident {
+                Some(ref field_name) => {
+                    let field_name = field_name.to_string();
+                    fields.push(quote! { .field(#field_name, #value) });
+                },
+                None => fields.push(quote! { .field(#value) }),
+            }
+        }
+        let debug =
+            match variant.fields {
+                Fields::Named(_) => quote! { formatter.debug_struct(#variant_name) #(#fields)* .finish() },
+                Fields::Unnamed(_) => quote! { formatter.debug_tuple(#variant_name) #(#fields)* .finish() },
+                Fields::Unit => quote! { formatter.write_str(#variant_name) },
+            };
+        arms.push(quote! {
+            #(#attrs)*
+            #pattern => #debug,
+        });
+    }
+
+    let mut generics = enum_item.generics.clone();
+    let type_params: Vec<_> = generics.params.iter()
+        .filter_map(|param| match *param {
+            GenericParam::Type(ref param) => Some(param.ident.clone()),
+            _ => None,
+        })
+        .collect();
+    for param in type_params {
+        generics.make_where_clause().predicates.push(parse_quote! { #param: ::std::fmt::Debug });
+    }
+    let typ = enum_type(enum_item);
+    let where_clause = gen_where_clause(&generics);
+    Ok(quote! {
+        impl #generics ::std::fmt::Debug for #typ #where_clause {
+            fn fmt(&self, formatter: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                match *self {
+                    #(#arms)*
+                }
+            }
+        }
+    })
+}
+
+/*
+ * The serde traits are implemented by converting the message to or from a private enum with the
+ * same variants which derives them, using the serde crate re-exported by relm.
+ */
+fn gen_serde(enum_item: &ItemEnum, krate: &Ident) -> Result<TokenStream> {
+    if !enum_item.generics.params.is_empty() {
+        return Err(Error::new(enum_item.generics.span(), "#[msg(serde)] is not supported on generic messages"));
+    }
+    let name = &enum_item.ident;
+    let name_string = name.to_string();
+    let serde_crate = format!("::{}::serde", krate);
+    let serialize_name = Ident::new("__RelmSerializeMsg", Span::call_site());
+    let deserialize_name = Ident::new("__RelmDeserializeMsg", Span::call_site());
+    let mut serialize_variants = vec![];
+    let mut deserialize_variants = vec![];
+    let mut to_serialize = vec![];
+    let mut from_deserialize = vec![];
+    for variant in &enum_item.variants {
+        let ident = &variant.ident;
+        let attrs = variant_attrs(variant);
+        let (pattern, bindings) = variant_pattern(name, variant, true);
+        let (shadow_pattern, _) = variant_pattern(&deserialize_name, variant, false);
+        let names = variant.fields.iter().map(|field| &field.ident);
+        let types: Vec<_> = variant.fields.iter().map(|field| &field.ty).collect();
+        let (serialize_fields, deserialize_fields, serialize_value, value) =
+            match variant.fields {
+                Fields::Named(_) => {
+                    let names: Vec<_> = names.collect();
+                    (quote! { { #(#names: &'a #types),* } }, quote! { { #(#names: #types),* } },
+                        quote! { { #(#names: #bindings),* } }, quote! { { #(#names: #bindings),* } })
+                },
+                Fields::Unnamed(_) =>
+                    (quote! { (#(&'a #types),*) }, quote! { (#(#types),*) }, quote! { (#(#bindings),*) },
+                        quote! { (#(#bindings),*) }),
+                Fields::Unit => (quote! {}, quote! {}, quote! {}, quote! {}),
+            };
+        serialize_variants.push(quote! { #(#attrs)* #ident #serialize_fields });
+        deserialize_variants.push(quote! { #(#attrs)* #ident #deserialize_fields });
+        to_serialize.push(quote! { #(#attrs)* #pattern => #serialize_name::#ident #serialize_value, });
+        from_deserialize.push(quote! { #(#attrs)* #shadow_pattern => #name::#ident #value, });
+    }
+    Ok(quote! {
+        const _: () = {
+            #[derive(::#krate::serde::Serialize)]
+            #[serde(crate = #serde_crate, rename = #name_string)]
+            enum #serialize_name<'a> {
+                #(#serialize_variants,)*
+                #[serde(skip)]
+                #[allow(dead_code)]
+                __RelmLifetime(::std::marker::PhantomData<&'a ()>),
+            }
+
+            #[derive(::#krate::serde::Deserialize)]
+            #[serde(crate = #serde_crate, rename = #name_string)]
+            enum #deserialize_name {
+                #(#deserialize_variants,)*
+            }
+
+            impl ::#krate::serde::Serialize for #name {
+                fn serialize<S: ::#krate::serde::Serializer>(&self, serializer: S)
+                    -> ::std::result::Result<S::Ok, S::Error>
+                {
+                    let msg = match *self {
+                        #(#to_serialize)*
+                    };
+                    ::#krate::serde::Serialize::serialize(&msg, serializer)
+                }
+            }
+
+            impl<'de> ::#krate::serde::Deserialize<'de> for #name {
+                fn deserialize<D: ::#krate::serde::Deserializer<'de>>(deserializer: D)
+                    -> ::std::result::Result<Self, D::Error>
+                {
+                    let msg = <#deserialize_name as ::#krate::serde::Deserialize>::deserialize(deserializer)?;
+                    Ok(match msg {
+                        #(#from_deserialize)*
+                    })
+                }
+            }
+        };
+    })
+}
+
+fn gen_inherent_impl(enum_item: &ItemEnum, methods: Vec<TokenStream>) -> TokenStream {
+    let generics = &enum_item.generics;
+    let typ = enum_type(enum_item);
+    let where_clause = gen_where_clause(generics);
+    quote! {
+        impl #generics #typ #where_clause {
+            #(#methods)*
+        }
+    }
+}
+
+fn enum_type(enum_item: &ItemEnum) -> TokenStream {
+    let name = &enum_item.ident;
+    let generics_without_bound = remove_generic_bounds(&enum_item.generics);
+    quote! {
+        #name #generics_without_bound
+    }
+}
+
+/// Check if the field has the #[msg(skip)] attribute, which hides its value in the Debug output.
+fn is_skipped(field: &Field) -> Result<bool> {
+    let mut skipped = false;
+    for option in msg_arguments(&field.attrs)? {
+        if option == "skip" {
+            skipped = true;
+        }
+        else {
+            return Err(Error::new(option.span(), format!("unknown #[msg] field option `{}`, expected `skip`", option)));
+        }
+    }
+    Ok(skipped)
+}
+
+fn msg_arguments(attrs: &[Attribute]) -> Result<Vec<Ident>> {
+    let mut arguments = vec![];
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident(MSG_ATTRIBUTE)) {
+        arguments.extend(attr.parse_args_with(Punctuated::<Ident, Token![,]>::parse_terminated)?);
+    }
+    Ok(arguments)
+}
+
+/// Get the attributes of the variant that must be kept on the generated match arms, like #[cfg].
+fn variant_attrs(variant: &Variant) -> Vec<&Attribute> {
+    variant.attrs.iter()
+        .filter(|attr| !attr.path.is_ident("doc") && !attr.path.is_ident(MSG_ATTRIBUTE))
+        .collect()
+}
+
+/// Generate a pattern binding every field of the variant.
+fn variant_pattern(name: &Ident, variant: &Variant, by_ref: bool) -> (TokenStream, Vec<Ident>) {
+    let ident = &variant.ident;
+    let by_ref =
+        if by_ref {
+            quote! { ref }
+        }
+        else {
+            quote! {}
+        };
+    match variant.fields {
+        Fields::Named(ref fields) => {
+            let bindings: Vec<_> = fields.named.iter()
+                .map(|field| field.ident.clone().expect("named field"))
+                .collect();
+            (quote! { #name::#ident { #(#by_ref #bindings),* } }, bindings)
+        },
+        Fields::Unnamed(ref fields) => {
+            let bindings: Vec<_> = (0..fields.unnamed.len())
+                .map(|index| Ident::new(&format!("__relm_field{}", index), Span::call_site()))
+                .collect();
+            (quote! { #name::#ident(#(#by_ref #bindings),*) }, bindings)
+        },
+        Fields::Unit => (quote! { #name::#ident }, vec![]),
+    }
+}
+
+fn to_snake_case(name: &str) -> String {
+    let mut snake_name = String::new();
+    for (index, character) in name.chars().enumerate() {
+        if character.is_uppercase() {
+            if index > 0 {
+                snake_name.push('_');
+            }
+            snake_name.extend(character.to_lowercase());
+        }
+        else {
+            snake_name.push(character);
+        }
+    }
+    snake_name
+}
@@ -0,0 +1,52 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! The #[relm_test::test] attribute, which runs the body of a test on the GTK thread of relm-test.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{ItemFn, parse2};
+use syn::parse::{Error, Result};
+use syn::spanned::Spanned;
+
+pub fn gen_test(attributes: TokenStream, input: TokenStream) -> Result<TokenStream> {
+    if !attributes.is_empty() {
+        return Err(Error::new(attributes.span(), "#[relm_test::test] does not take arguments"));
+    }
+    let ItemFn { attrs, vis, sig, block } = parse2(input)?;
+    if let Some(asyncness) = sig.asyncness {
+        return Err(Error::new(asyncness.span(), "async test functions are not supported"));
+    }
+    if !sig.generics.params.is_empty() {
+        return Err(Error::new(sig.generics.span(), "test functions cannot be generic"));
+    }
+    if !sig.inputs.is_empty() {
+        return Err(Error::new(sig.inputs.span(), "test functions cannot take arguments"));
+    }
+    // The absolute path avoids calling this attribute again when it is imported as `test`.
+    Ok(quote! {
+        #[::core::prelude::v1::test]
+        #(#attrs)*
+        #vis #sig {
+            ::relm_test::run_on_gtk_thread(move || #block)
+        }
+    })
+}
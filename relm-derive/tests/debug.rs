@@ -0,0 +1,50 @@
+// The #[widget(debug)] attribute emits a deprecation warning showing where the code was written.
+#![allow(deprecated)]
+
+use std::fs;
+use std::path::PathBuf;
+
+use gtk::prelude::{GtkWindowExt, WidgetExt};
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+#[derive(Msg)]
+pub enum Msg {
+    Quit,
+}
+
+#[widget(debug)]
+impl Widget for DebugWin {
+    fn model() {}
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Msg::Quit => gtk::main_quit(),
+        }
+    }
+
+    view! {
+        gtk::Window {
+            title: "Debug",
+            #[container]
+            gtk::Box {
+                gtk::Label {
+                },
+            },
+            delete_event(_, _) => (Msg::Quit, gtk::Inhibit(false)),
+        }
+    }
+}
+
+#[test]
+fn debug_dump() {
+    let target_dir = option_env!("CARGO_TARGET_DIR")
+        .map(|dir| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(dir))
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("target"));
+    let path = target_dir.join("relm-derive").join("DebugWin.rs");
+    let code = fs::read_to_string(&path).expect("read the code dumped by #[widget(debug)]");
+    assert!(code.contains("pub struct __DebugWinWidgets"), "widgets struct missing in:\n{}", code);
+    assert!(code.contains("type Streams"), "Streams missing in:\n{}", code);
+    assert!(code.contains("impl ::relm::Container for DebugWin"), "Container impl missing in:\n{}", code);
+    assert!(code.contains("impl ::relm::Update for DebugWin"), "Update impl missing in:\n{}", code);
+}
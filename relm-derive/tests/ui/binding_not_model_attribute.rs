@@ -0,0 +1,27 @@
+#![allow(unused_imports)]
+
+use relm::Widget;
+use relm_derive::widget;
+
+pub struct Model {
+    text: String,
+}
+
+#[widget]
+impl Widget for Foo {
+    fn model() -> Model {
+        Model {
+            text: String::new(),
+        }
+    }
+
+    fn update(&mut self, _: ()) {}
+
+    view! {
+        gtk::Entry {
+            text <=> self.model.text.clone(),
+        }
+    }
+}
+
+fn main() {}
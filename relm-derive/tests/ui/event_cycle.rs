@@ -0,0 +1,38 @@
+#![deny(deprecated)]
+
+use gtk::prelude::{EditableSignals, EntryExt};
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+pub struct Model {
+    text: String,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Change(String),
+}
+
+#[widget]
+impl Widget for Foo {
+    fn model() -> Model {
+        Model {
+            text: String::new(),
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Msg::Change(text) => self.model.text = text.to_uppercase(),
+        }
+    }
+
+    view! {
+        gtk::Entry {
+            text: &self.model.text,
+            changed(entry) => Msg::Change(entry.text().to_string()),
+        }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,33 @@
+#![allow(unused_imports)]
+
+use gtk::prelude::ButtonExt;
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+pub struct Model {
+    count: u32,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Save(u32),
+}
+
+#[widget]
+impl Widget for Foo {
+    fn model() -> Model {
+        Model {
+            count: 0,
+        }
+    }
+
+    fn update(&mut self, _: Msg) {}
+
+    view! {
+        gtk::Button {
+            clicked => Msg::Save(self.count()),
+        }
+    }
+}
+
+fn main() {}
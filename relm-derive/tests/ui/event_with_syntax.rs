@@ -0,0 +1,36 @@
+#![allow(unused_imports)]
+#![deny(deprecated)]
+
+use std::rc::Rc;
+
+use gtk::prelude::ButtonExt;
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+pub struct Model {
+    count: Rc<u32>,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Save(u32),
+}
+
+#[widget]
+impl Widget for Foo {
+    fn model() -> Model {
+        Model {
+            count: Rc::new(0),
+        }
+    }
+
+    fn update(&mut self, _: Msg) {}
+
+    view! {
+        gtk::Button {
+            clicked(_) with (count) => Msg::Save(*count),
+        }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,25 @@
+#![allow(unused_imports)]
+
+use gtk::Inhibit;
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+#[derive(Msg)]
+pub enum Msg {
+    Quit,
+}
+
+#[widget]
+impl Widget for Foo {
+    fn model() {}
+
+    fn update(&mut self, _: Msg) {}
+
+    view! {
+        gtk::Window {
+            delete_event(_, _) => other@(Msg::Quit, Inhibit(false)),
+        }
+    }
+}
+
+fn main() {}
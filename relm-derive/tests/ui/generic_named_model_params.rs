@@ -0,0 +1,26 @@
+#![allow(unused_imports)]
+
+use relm::Widget;
+use relm_derive::widget;
+
+pub struct Model<T> {
+    value: T,
+}
+
+#[widget]
+impl<T: Clone + 'static> Widget for Foo<T> {
+    fn model(value: T, #[default(1)] step: i32) -> Model<T> {
+        Model {
+            value,
+        }
+    }
+
+    fn update(&mut self, _: ()) {}
+
+    view! {
+        gtk::Label {
+        }
+    }
+}
+
+fn main() {}
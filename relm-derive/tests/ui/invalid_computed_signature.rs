@@ -0,0 +1,31 @@
+#![allow(unused_imports)]
+
+use relm::Widget;
+use relm_derive::widget;
+
+pub struct Model {
+    count: u32,
+}
+
+#[widget]
+impl Widget for Foo {
+    fn model() -> Model {
+        Model {
+            count: 0,
+        }
+    }
+
+    fn update(&mut self, _: ()) {}
+
+    #[computed]
+    fn double(&mut self, factor: u32) -> u32 {
+        self.model.count * factor
+    }
+
+    view! {
+        gtk::Window {
+        }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+#![allow(unused_imports)]
+
+use relm::Widget;
+use relm_derive::widget;
+
+#[widget]
+impl Widget for Foo {
+    fn model() {}
+
+    fn update(&mut self) {}
+
+    view! {
+        gtk::Window {
+        }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+#![allow(unused_imports)]
+
+use relm::Widget;
+use relm_derive::widget;
+
+#[widget]
+impl Widget for Foo {
+    fn model() {}
+
+    fn update(&mut self, _: ()) {}
+
+    view! {
+        "does-not-exist.relm"
+    }
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+#![allow(unused_imports)]
+
+use relm::Widget;
+use relm_derive::widget;
+
+#[widget]
+impl Widget for Foo {
+    fn update(&mut self, _: ()) {}
+
+    view! {
+        gtk::Window {
+        }
+    }
+}
+
+fn main() {}
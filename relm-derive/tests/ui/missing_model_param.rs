@@ -0,0 +1,41 @@
+#![allow(unused_imports)]
+
+use relm::Widget;
+use relm_derive::widget;
+
+pub struct Model {
+    start: i32,
+    step: i32,
+}
+
+#[widget]
+impl Widget for Counter {
+    fn model(#[default(0)] start: i32, step: i32) -> Model {
+        Model {
+            start,
+            step,
+        }
+    }
+
+    fn update(&mut self, _: ()) {}
+
+    view! {
+        gtk::Label {
+        }
+    }
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() {}
+
+    fn update(&mut self, _: ()) {}
+
+    view! {
+        gtk::Window {
+            Counter(start: 2),
+        }
+    }
+}
+
+fn main() {}
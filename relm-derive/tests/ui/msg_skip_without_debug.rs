@@ -0,0 +1,9 @@
+use relm_derive::Msg;
+
+#[derive(Msg)]
+#[msg(accessors)]
+pub enum Msg {
+    Login(String, #[msg(skip)] String),
+}
+
+fn main() {}
@@ -0,0 +1,21 @@
+#![allow(unused_imports)]
+
+use relm::Widget;
+use relm_derive::widget;
+
+#[widget]
+impl Widget for Foo {
+    fn model() {}
+
+    fn update(&mut self, _: ()) {}
+
+    view! {
+        gtk::Box {
+            #[container="right"]
+            gtk::Box {
+            },
+        }
+    }
+}
+
+fn main() {}
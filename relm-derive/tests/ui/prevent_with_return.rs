@@ -0,0 +1,24 @@
+#![allow(unused_imports)]
+
+use gtk::Inhibit;
+use relm::Widget;
+use relm_derive::widget;
+
+pub enum Msg {
+    Pressed,
+}
+
+#[widget]
+impl Widget for Foo {
+    fn model() {}
+
+    fn update(&mut self, _: Msg) {}
+
+    view! {
+        gtk::Window {
+            key_press_event(_, _).prevent => (Msg::Pressed, Inhibit(false)),
+        }
+    }
+}
+
+fn main() {}
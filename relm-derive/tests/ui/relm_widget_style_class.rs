@@ -0,0 +1,41 @@
+#![allow(unused_imports)]
+
+use relm::Widget;
+use relm_derive::widget;
+
+#[widget]
+impl Widget for Child {
+    fn model() {}
+
+    fn update(&mut self, _: ()) {}
+
+    view! {
+        gtk::Label {
+        }
+    }
+}
+
+pub struct Model {
+    selected: bool,
+}
+
+#[widget]
+impl Widget for Foo {
+    fn model() -> Model {
+        Model {
+            selected: false,
+        }
+    }
+
+    fn update(&mut self, _: ()) {}
+
+    view! {
+        gtk::Window {
+            #[style_class(selected = self.model.selected)]
+            Child {
+            },
+        }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use relm_derive::test;
+
+#[test]
+fn with_arguments(count: u32) {
+    assert_eq!(count, 0);
+}
+
+fn main() {}
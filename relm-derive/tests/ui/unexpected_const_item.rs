@@ -0,0 +1,20 @@
+#![allow(unused_imports)]
+
+use relm::Widget;
+use relm_derive::widget;
+
+#[widget]
+impl Widget for Foo {
+    const VALUE: i32 = 0;
+
+    fn model() {}
+
+    fn update(&mut self, _: ()) {}
+
+    view! {
+        gtk::Window {
+        }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,26 @@
+#![allow(unused_imports)]
+
+use gtk::prelude::EntryExt;
+use relm::Widget;
+use relm_derive::widget;
+
+pub enum Msg {
+    Search(String),
+}
+
+#[widget]
+impl Widget for Foo {
+    fn model() {}
+
+    fn update(&mut self, _: Msg) {}
+
+    view! {
+        gtk::Window {
+            gtk::Entry {
+                changed(entry).throttle(100) => Msg::Search(entry.text().to_string()),
+            },
+        }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,39 @@
+#![allow(unused_imports)]
+
+use relm::Widget;
+use relm_derive::widget;
+
+pub struct Model {
+    step: i32,
+}
+
+#[widget]
+impl Widget for Counter {
+    fn model(#[default(1)] step: i32) -> Model {
+        Model {
+            step,
+        }
+    }
+
+    fn update(&mut self, _: ()) {}
+
+    view! {
+        gtk::Label {
+        }
+    }
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() {}
+
+    fn update(&mut self, _: ()) {}
+
+    view! {
+        gtk::Window {
+            Counter(stride: 2),
+        }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use relm_derive::Msg;
+
+#[derive(Msg)]
+#[msg(accessors, json)]
+pub enum Msg {
+    Quit,
+}
+
+fn main() {}
@@ -0,0 +1,41 @@
+#![allow(unused_imports)]
+
+use relm::Widget;
+use relm_derive::widget;
+
+#[widget]
+impl Widget for Card {
+    fn model() {}
+
+    fn update(&mut self, _: ()) {}
+
+    view! {
+        gtk::Box {
+            #[container="header"]
+            gtk::Box {
+            },
+            #[container]
+            gtk::Box {
+            },
+        }
+    }
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() {}
+
+    fn update(&mut self, _: ()) {}
+
+    view! {
+        gtk::Window {
+            Card {
+                #[slot="footer"]
+                gtk::Label {
+                },
+            },
+        }
+    }
+}
+
+fn main() {}
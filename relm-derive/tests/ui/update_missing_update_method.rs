@@ -0,0 +1,10 @@
+#![allow(unused_imports)]
+
+use relm_derive::update;
+
+#[update]
+impl Update for Foo {
+    fn model() {}
+}
+
+fn main() {}
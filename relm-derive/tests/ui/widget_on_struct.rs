@@ -0,0 +1,7 @@
+use relm_derive::widget;
+
+#[widget]
+struct Win {
+}
+
+fn main() {}
@@ -51,12 +51,10 @@ use relm::{
     connect_stream,
     EventStream,
     Relm,
-    Update,
-    UpdateNew,
     Widget,
     execute,
 };
-use relm_derive::{Msg, widget};
+use relm_derive::{Msg, update, widget};
 use simplelog::{ColorChoice, Config, TermLogger, TerminalMode};
 use simplelog::LevelFilter::Warn;
 use uhttp_uri::HttpUri;
@@ -186,7 +184,7 @@ impl Drop for Win {
     }
 }
 
-struct HttpModel {
+pub struct HttpModel {
     buffer: Vec<u8>,
     chunked: bool,
     content_length: Option<usize>,
@@ -196,7 +194,7 @@ struct HttpModel {
     url: String,
 }
 
-struct Bytes {
+pub struct Bytes {
     bytes: RefCell<Option<Vec<u8>>>,
 }
 
@@ -213,7 +211,7 @@ impl Bytes {
 }
 
 #[derive(Msg)]
-enum HttpMsg {
+pub enum HttpMsg {
     Connection(SocketConnection),
     DataRead(Bytes),
     Read((Vec<u8>, usize)),
@@ -223,15 +221,8 @@ enum HttpMsg {
 
 unsafe impl Send for HttpMsg {}
 
-struct Http {
-    model: HttpModel,
-}
-
+#[update]
 impl Update for Http {
-    type Model = HttpModel;
-    type ModelParam = String;
-    type Msg = HttpMsg;
-
     fn model(relm: &Relm<Self>, url: String) -> HttpModel {
         HttpModel {
             buffer: vec![],
@@ -333,14 +324,6 @@ impl Update for Http {
     }
 }
 
-impl UpdateNew for Http {
-    fn new(_relm: &Relm<Self>, model: HttpModel) -> Self {
-        Http {
-            model,
-        }
-    }
-}
-
 fn find_crlf(buffer: &[u8]) -> Option<usize> {
     for i in 0..buffer.len() {
         if buffer[i..].len() < 4 {
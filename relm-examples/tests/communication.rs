@@ -40,6 +40,7 @@ use relm::{
     Component,
     ContainerWidget,
     Relm,
+    StreamHandle,
     Update,
     Widget,
     WidgetTest,
@@ -118,7 +119,7 @@ struct CounterModel {
     counter: i32,
 }
 
-#[derive(Msg)]
+#[derive(Clone, Msg)]
 enum CounterMsg {
     Decrement,
     Increment,
@@ -194,7 +195,7 @@ struct Model {
     counter: i32,
 }
 
-#[derive(Msg)]
+#[derive(Clone, Msg)]
 enum Msg {
     TextChange(String),
     Quit,
@@ -212,6 +213,12 @@ struct Components {
     _text: Component<Text>,
 }
 
+#[derive(Clone)]
+struct Streams {
+    counter1: StreamHandle<CounterMsg>,
+    counter2: StreamHandle<CounterMsg>,
+}
+
 #[derive(Clone)]
 struct Widgets {
     counter1: gtk::Box,
@@ -298,9 +305,13 @@ impl Widget for Win {
 }
 
 impl WidgetTest for Win {
-    type Streams = ();
+    type Streams = Streams;
 
     fn get_streams(&self) -> Self::Streams {
+        Streams {
+            counter1: self._components._counter1.stream(),
+            counter2: self._components._counter2.stream(),
+        }
     }
 
     type Widgets = Widgets;
@@ -319,13 +330,21 @@ mod tests {
     use gtk::{Button, Entry, Label, prelude::LabelExt};
 
     use gtk_test::{assert_text, find_child_by_name};
-    use relm_test::{click, enter_keys};
+    use relm_test::{MessageLog, assert_count, assert_no_message, assert_sequence, click, enter_keys};
 
+    use crate::CounterMsg::{Decrement, Increment};
+    use crate::Msg::{Quit, TextChange};
     use crate::Win;
 
     #[test]
     fn label_change() {
-        let (_component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        let (component, streams, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        let counter1_log = MessageLog::new();
+        counter1_log.record(&streams.counter1);
+        let counter2_log = MessageLog::new();
+        counter2_log.record(&streams.counter2);
+        let win_log = MessageLog::new();
+        win_log.record(&component.stream());
         let dec_button = &widgets.dec_button;
         let label1: Label = find_child_by_name(&widgets.counter1, "label").expect("label1");
         let inc_button1: Button = find_child_by_name(&widgets.counter1, "inc_button").expect("button1");
@@ -361,6 +380,12 @@ mod tests {
         assert_text!(label1, -2);
         assert_text!(label2, -2);
 
+        assert_sequence!(counter1_log, [Decrement, Decrement, Increment, Increment, Decrement, Decrement]);
+        // Each increment of the first counter decrements the second one.
+        assert_sequence!(counter2_log, [Decrement, Decrement]);
+        assert_no_message!(win_log);
+        counter1_log.clear();
+
         assert_text!(label, "");
 
         enter_keys(&entry, "t");
@@ -372,5 +397,10 @@ mod tests {
         assert_text!(label, 2);
         assert_text!(label1, 0);
         assert_text!(text_label, "et");
+
+        // Every change of the text increments the first counter.
+        assert_sequence!(counter1_log, [Increment, Increment]);
+        assert_count!(win_log, TextChange(_), 2);
+        assert_no_message!(win_log, Quit);
     }
 }
@@ -0,0 +1,174 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+use gtk::{
+    Inhibit,
+    prelude::ButtonExt,
+    prelude::LabelExt,
+    prelude::OrientableExt,
+    prelude::WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+use self::Msg::*;
+
+pub struct Model {
+    fees: Vec<u32>,
+    fees_text: String,
+    price: u32,
+    quantity: u32,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Add,
+    AddFee,
+    Discount,
+    Quit,
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> Model {
+        Model {
+            fees: vec![],
+            fees_text: String::new(),
+            price: 10,
+            quantity: 1,
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Add => self.model.quantity += 1,
+            AddFee => {
+                // Calling a #[computed] method always uses the current model, even after an
+                // in-place mutation.
+                self.model.fees.push(5);
+                self.model.fees_text = format!("Fees: {}$", self.fees_total());
+            },
+            Discount => self.model.price /= 2,
+            Quit => gtk::main_quit(),
+        }
+    }
+
+    // The labels using these methods are updated when price or quantity is assigned.
+    #[computed]
+    fn total(&self) -> u32 {
+        self.model.price * self.model.quantity
+    }
+
+    #[computed]
+    fn summary(&self) -> String {
+        format!("{} × {}$ = {}$", self.model.quantity, self.model.price, self.total())
+    }
+
+    #[computed]
+    fn fees_total(&self) -> u32 {
+        self.model.fees.iter().sum()
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                #[name="add_button"]
+                gtk::Button {
+                    clicked => Add,
+                    label: "+",
+                },
+                #[name="discount_button"]
+                gtk::Button {
+                    clicked => Discount,
+                    label: "-50%",
+                },
+                #[name="fee_button"]
+                gtk::Button {
+                    clicked => AddFee,
+                    label: "Add fee",
+                },
+                #[name="fees"]
+                gtk::Label {
+                    text: &self.model.fees_text,
+                },
+                #[name="summary"]
+                gtk::Label {
+                    text: &self.summary(),
+                },
+                #[name="expensive"]
+                gtk::Label {
+                    text: "Expensive",
+                    visible: self.total() > 20,
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+fn main() {
+    Win::run(()).expect("Win::run failed");
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk::prelude::{LabelExt, WidgetExt};
+
+    use gtk_test::assert_text;
+    use relm_test::click;
+
+    use crate::Win;
+
+    #[test]
+    fn computed_values() {
+        let (_component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        let add_button = &widgets.add_button;
+        let discount_button = &widgets.discount_button;
+        let summary = &widgets.summary;
+        let expensive = &widgets.expensive;
+
+        assert_text!(summary, "1 × 10$ = 10$");
+        assert!(!expensive.is_visible());
+
+        click(add_button);
+        click(add_button);
+        assert_text!(summary, "3 × 10$ = 30$");
+        assert!(expensive.is_visible());
+
+        click(discount_button);
+        assert_text!(summary, "3 × 5$ = 15$");
+        assert!(!expensive.is_visible());
+    }
+
+    #[test]
+    fn computed_after_in_place_mutation() {
+        let (_component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        let fee_button = &widgets.fee_button;
+        let fees = &widgets.fees;
+
+        click(fee_button);
+        assert_text!(fees, "Fees: 5$");
+
+        click(fee_button);
+        assert_text!(fees, "Fees: 10$");
+    }
+}
@@ -0,0 +1,170 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+use gdk::keys::constants as key;
+use gtk::{
+    EditableSignals,
+    Inhibit,
+    prelude::ButtonExt,
+    prelude::EntryExt,
+    prelude::LabelExt,
+    prelude::OrientableExt,
+    prelude::WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+use self::Msg::*;
+
+pub struct Model {
+    open_count: u32,
+    query: String,
+    search_count: u32,
+    submitted: String,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Open,
+    Search(String),
+    Submit,
+    Quit,
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> Model {
+        Model {
+            open_count: 0,
+            query: String::new(),
+            search_count: 0,
+            submitted: String::new(),
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Open => self.model.open_count += 1,
+            Search(query) => {
+                self.model.search_count += 1;
+                self.model.query = query;
+            },
+            Submit => self.model.submitted = self.model.query.clone(),
+            Quit => gtk::main_quit(),
+        }
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                #[name="entry"]
+                gtk::Entry {
+                    // Only search when the user stopped typing for 100ms.
+                    changed(entry).debounce(100) => Search(entry.text().to_string()),
+                    // The Return key is not propagated to the entry, the other keys are.
+                    key_press_event(_, event).prevent if event.keyval() == key::Return => Submit,
+                },
+                #[name="open_button"]
+                gtk::Button {
+                    clicked.once => Open,
+                    label: "Open",
+                },
+                #[name="search_label"]
+                gtk::Label {
+                    text: &format!("{} ({})", self.model.query, self.model.search_count),
+                },
+                #[name="submit_label"]
+                gtk::Label {
+                    text: &self.model.submitted,
+                },
+                #[name="open_label"]
+                gtk::Label {
+                    text: &self.model.open_count.to_string(),
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+fn main() {
+    Win::run(()).expect("Win::run failed");
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use gdk::keys::constants as key;
+    use gtk::prelude::{EntryExt, LabelExt};
+
+    use gtk_test::{assert_text, wait};
+    use relm_test::{advance, click, enter_key, enter_keys, use_virtual_clock};
+
+    use crate::Win;
+
+    #[relm_test::test]
+    fn event_modifiers() {
+        let (_component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        let entry = &widgets.entry;
+        let open_button = &widgets.open_button;
+        let search_label = &widgets.search_label;
+        let submit_label = &widgets.submit_label;
+        let open_label = &widgets.open_label;
+
+        enter_keys(entry, "relm");
+        assert_text!(search_label, " (0)");
+        wait(200);
+        assert_text!(search_label, "relm (1)");
+
+        enter_key(entry, key::Return);
+        assert_text!(submit_label, "relm");
+        assert_text!(entry, "relm");
+
+        click(open_button);
+        click(open_button);
+        assert_text!(open_label, "1");
+    }
+
+    #[relm_test::test]
+    fn debounce_with_virtual_clock() {
+        use_virtual_clock();
+        let (_component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        let entry = &widgets.entry;
+        let search_label = &widgets.search_label;
+
+        enter_keys(entry, "re");
+        advance(Duration::from_millis(99));
+        assert_text!(search_label, " (0)");
+
+        // Typing again restarts the delay.
+        enter_keys(entry, "lm");
+        advance(Duration::from_millis(99));
+        assert_text!(search_label, " (0)");
+
+        advance(Duration::from_millis(1));
+        assert_text!(search_label, "relm (1)");
+
+        advance(Duration::from_secs(10));
+        assert_text!(search_label, "relm (1)");
+    }
+}
@@ -0,0 +1,177 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use gtk::{
+    Inhibit,
+    prelude::ButtonExt,
+    prelude::LabelExt,
+    prelude::OrientableExt,
+    prelude::WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::{Relm, Widget};
+use relm_derive::{Msg, update, widget};
+
+use self::CounterMsg::*;
+use self::Msg::*;
+
+pub struct CounterModel {
+    displayed: i32,
+    max: i32,
+    relm: Relm<Counter>,
+    value: i32,
+}
+
+#[derive(Clone, Debug, Msg)]
+pub enum CounterMsg {
+    Add(i32),
+    Changed(i32),
+    Reset,
+}
+
+// A counter which stays between 0 and max.
+#[update]
+impl Update for Counter {
+    fn model(relm: &Relm<Self>, max: i32) -> CounterModel {
+        CounterModel {
+            displayed: 0,
+            max,
+            relm: relm.clone(),
+            value: 0,
+        }
+    }
+
+    fn update(&mut self, event: CounterMsg) {
+        match event {
+            Add(step) => {
+                self.model.value = self.model.value.saturating_add(step).clamp(0, self.model.max);
+                self.model.relm.stream().emit(Changed(self.model.value));
+            },
+            Changed(value) => self.model.displayed = value,
+            Reset => {
+                self.model.value = 0;
+                self.model.relm.stream().emit(Changed(0));
+            },
+        }
+    }
+}
+
+pub struct Model {
+    value: i32,
+}
+
+#[derive(Clone, Debug, Msg)]
+pub enum Msg {
+    Decrement,
+    Increment,
+    Quit,
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> Model {
+        Model {
+            value: 0,
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Decrement => self.model.value -= 1,
+            Increment => self.model.value += 1,
+            Quit => gtk::main_quit(),
+        }
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                gtk::Button {
+                    clicked => Increment,
+                    label: "+",
+                },
+                #[name="label"]
+                gtk::Label {
+                    text: &self.model.value.to_string(),
+                },
+                gtk::Button {
+                    clicked => Decrement,
+                    label: "-",
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+fn main() {
+    Win::run(()).expect("Win::run failed");
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic;
+
+    use gtk::prelude::LabelExt;
+
+    use relm_test::fuzz::{check_updates, check_widget_updates, proptest::prelude::*};
+
+    use crate::{Counter, CounterMsg, Msg, Win};
+
+    fn counter_messages() -> impl Strategy<Value=CounterMsg> {
+        prop_oneof![
+            (-20..20).prop_map(CounterMsg::Add),
+            Just(CounterMsg::Reset),
+        ]
+    }
+
+    #[test]
+    fn counter_stays_in_bounds() {
+        check_updates::<Counter, _, _>(10, counter_messages(), |counter| {
+            assert!(counter.model.value >= 0 && counter.model.value <= 10);
+            // The Changed message emitted by update() was already processed.
+            assert_eq!(counter.model.displayed, counter.model.value);
+        });
+    }
+
+    #[test]
+    fn counter_failing_invariant() {
+        let result = panic::catch_unwind(|| {
+            check_updates::<Counter, _, _>(10, counter_messages(), |counter| {
+                assert_eq!(counter.model.value, 0);
+            });
+        });
+        let payload = result.expect_err("the invariant should fail");
+        let message = payload.downcast_ref::<String>().expect("panic message");
+        // Any increment breaks the invariant, so the sequence is shrunk to the smallest one.
+        let expected = format!("Minimal failing sequence of messages: {:#?}", [CounterMsg::Add(1)]);
+        assert!(message.ends_with(&expected), "unexpected panic message: {}", message);
+    }
+
+    #[relm_test::test]
+    fn label_shows_value() {
+        let messages = prop_oneof![Just(Msg::Increment), Just(Msg::Decrement)];
+        check_widget_updates::<Win, _, _>((), messages, |win| {
+            assert_eq!(win.widgets.label.text(), win.model.value.to_string());
+        });
+    }
+}
@@ -0,0 +1,172 @@
+/*
+ * Copyright (c) 2017-2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+use gtk::{
+    Inhibit,
+    prelude::ButtonExt,
+    prelude::LabelExt,
+    prelude::OrientableExt,
+    prelude::WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::{Relm, Widget};
+use relm_derive::{Msg, widget};
+
+use self::ButtonMsg::*;
+use self::Msg::*;
+
+pub struct ButtonModel {
+    relm: Relm<PositionButton>,
+}
+
+#[derive(Msg)]
+#[msg(accessors, debug, serde, variants)]
+pub enum ButtonMsg {
+    Click,
+    // The payload of a relm event can be a tuple.
+    Moved((i32, i32)),
+}
+
+#[widget]
+impl Widget for PositionButton {
+    fn model(relm: &Relm<Self>, _: ()) -> ButtonModel {
+        ButtonModel {
+            relm: relm.clone(),
+        }
+    }
+
+    fn update(&mut self, event: ButtonMsg) {
+        match event {
+            Click => self.model.relm.stream().emit(Moved((3, 4))),
+            Moved(_) => (),
+        }
+    }
+
+    view! {
+        gtk::Button {
+            clicked => Click,
+            label: "Move",
+        },
+    }
+}
+
+pub struct Model {
+    position: (i32, i32),
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Position(i32, i32),
+    Quit,
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> Model {
+        Model {
+            position: (0, 0),
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Position(x, y) => self.model.position = (x, y),
+            Quit => gtk::main_quit(),
+        }
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                #[name="button"]
+                PositionButton {
+                    Moved((x, y)) => Position(x, y),
+                },
+                #[name="label"]
+                gtk::Label {
+                    text: &format!("{}, {}", self.model.position.0, self.model.position.1),
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+fn main() {
+    Win::run(()).expect("Win::run failed");
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk::prelude::LabelExt;
+
+    use gtk_test::assert_text;
+    use relm_derive::Msg;
+    use relm_test::click;
+
+    use crate::ButtonMsg::{self, Click, Moved};
+    use crate::Win;
+
+    #[test]
+    fn tuple_payload() {
+        let (_component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        let button = &widgets.button;
+        let label = &widgets.label;
+
+        assert_text!(label, "0, 0");
+
+        click(button);
+        assert_text!(label, "3, 4");
+    }
+
+    #[test]
+    fn msg_helpers() {
+        let msg = Moved((3, 4));
+        assert!(msg.is_moved());
+        assert!(!msg.is_click());
+        assert_eq!(msg.as_moved(), Some(&(3, 4)));
+        assert_eq!(Click.as_moved(), None);
+        assert_eq!(ButtonMsg::VARIANTS, &["Click", "Moved"]);
+        assert_eq!(msg.variant_index(), 1);
+        assert_eq!(format!("{:?}", msg), "Moved((3, 4))");
+
+        let json = serde_json::to_string(&msg).expect("serialize");
+        assert_eq!(json, r#"{"Moved":[3,4]}"#);
+        let msg: ButtonMsg = serde_json::from_str(&json).expect("deserialize");
+        assert_eq!(msg.as_moved(), Some(&(3, 4)));
+    }
+
+    #[test]
+    fn variants_with_cfg() {
+        #[derive(Msg)]
+        #[msg(variants)]
+        enum CfgMsg {
+            First,
+            #[cfg(any())]
+            Disabled,
+            Last,
+        }
+
+        assert_eq!(CfgMsg::VARIANTS, &["First", "Last"]);
+        assert_eq!(CfgMsg::First.variant_index(), 0);
+        assert_eq!(CfgMsg::Last.variant_index(), 1);
+    }
+}
@@ -0,0 +1,138 @@
+/*
+ * Copyright (c) 2017-2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+use gtk::{
+    Inhibit,
+    prelude::ButtonExt,
+    prelude::OrientableExt,
+    prelude::WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+use self::CounterMsg::*;
+use self::Msg::*;
+
+pub struct CounterModel {
+    step: i32,
+    title: &'static str,
+    value: i32,
+}
+
+#[derive(Msg)]
+pub enum CounterMsg {
+    Increment,
+}
+
+#[widget]
+impl Widget for Counter {
+    // The parameters without a default value must be set by the parent widget.
+    fn model(#[default("Count")] title: &'static str, #[default(0)] start: i32, step: i32) -> CounterModel {
+        CounterModel {
+            step,
+            title,
+            value: start,
+        }
+    }
+
+    fn update(&mut self, event: CounterMsg) {
+        match event {
+            Increment => self.model.value += self.model.step,
+        }
+    }
+
+    view! {
+        gtk::Button {
+            clicked => Increment,
+            label: &format!("{}: {}", self.model.title, self.model.value),
+        },
+    }
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Quit,
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> () {
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Quit => gtk::main_quit(),
+        }
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                // The parameters are set by name, in any order.
+                #[name="even_counter"]
+                Counter(step: 2, title: "Even"),
+                // The omitted parameters get their default value.
+                #[name="counter"]
+                Counter(step: 1),
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+fn main() {
+    Win::run(()).expect("Win::run failed");
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk::prelude::ButtonExt;
+
+    use gtk_test::assert_label;
+    use relm::ModelParams;
+    use relm_test::click;
+
+    use crate::{CounterParams, Win};
+
+    #[test]
+    fn named_model_params() {
+        let (_component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        let even_counter = &widgets.even_counter;
+        let counter = &widgets.counter;
+
+        assert_label!(even_counter, "Even: 0");
+        assert_label!(counter, "Count: 0");
+
+        click(even_counter);
+        click(counter);
+        assert_label!(even_counter, "Even: 2");
+        assert_label!(counter, "Count: 1");
+    }
+
+    #[test]
+    fn params_builder() {
+        let params = CounterParams::builder().start(5).step(3).build();
+        assert_eq!(params.title, "Count");
+        assert_eq!(params.start, 5);
+        assert_eq!(params.step, 3);
+    }
+}
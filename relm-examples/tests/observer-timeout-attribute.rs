@@ -0,0 +1,139 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use gtk::{
+    Inhibit,
+    prelude::ButtonExt,
+    prelude::LabelExt,
+    prelude::OrientableExt,
+    prelude::WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+use self::Msg::*;
+
+pub struct Model {
+    counter: i32,
+}
+
+#[derive(Clone, Msg)]
+pub enum Msg {
+    Decrement,
+    Increment,
+    Quit,
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> Model {
+        Model {
+            counter: 0,
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Decrement => self.model.counter -= 1,
+            Increment => self.model.counter += 1,
+            Quit => gtk::main_quit(),
+        }
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                #[name="inc_button"]
+                gtk::Button {
+                    clicked => Increment,
+                    label: "+",
+                },
+                #[name="label"]
+                gtk::Label {
+                    text: &self.model.counter.to_string(),
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+fn main() {
+    Win::run(()).expect("Win::run failed");
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use gtk::prelude::LabelExt;
+    use gtk_test::assert_text;
+    use relm_test::{Timeout, click, relm_observer_new};
+
+    use crate::Msg::{Decrement, Increment};
+    use crate::Win;
+
+    #[relm_test::test]
+    fn wait_for_missing_message() {
+        let (component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        let observer = relm_observer_new!(component, Decrement);
+
+        click(&widgets.inc_button);
+        assert_text!(widgets.label, 1);
+
+        let start = Instant::now();
+        let result = observer.wait_timeout(Duration::from_millis(200));
+        assert!(start.elapsed() >= Duration::from_millis(200));
+        let timeout: Timeout =
+            match result {
+                Ok(msg) => panic!("Unexpected message: {}", observer.describe(&msg)),
+                Err(timeout) => timeout,
+            };
+        assert_eq!(timeout.expected, "Decrement");
+        assert_eq!(timeout.duration, Duration::from_millis(200));
+        assert_eq!(timeout.received, ["Increment"]);
+        assert_eq!(timeout.to_string(), "Timed out after 200ms waiting for `Decrement`, received: Increment.");
+    }
+
+    #[relm_test::test]
+    fn wait_without_messages() {
+        let (component, _, _widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        let observer = relm_observer_new!(component, Increment);
+
+        let timeout = observer.wait_timeout(Duration::from_millis(50)).err().expect("timeout");
+        assert_eq!(timeout.expected, "Increment");
+        assert!(timeout.received.is_empty());
+        assert_eq!(timeout.to_string(), "Timed out after 50ms waiting for `Increment`, no message was received.");
+    }
+
+    #[relm_test::test]
+    #[should_panic(expected = "Timed out after 100ms waiting for `Decrement`, received: Increment.")]
+    fn wait_panics_after_default_timeout() {
+        relm_test::set_default_timeout(Duration::from_millis(100));
+        let (component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        let observer = relm_observer_new!(component, Decrement);
+
+        click(&widgets.inc_button);
+        observer.wait();
+    }
+}
@@ -0,0 +1,224 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use gdk::{EventButton, EventKey, EventMask, EventScroll, ModifierType};
+use gdk::keys::constants as key;
+use gtk::{
+    GestureLongPress,
+    Inhibit,
+    prelude::LabelExt,
+    prelude::OrientableExt,
+    prelude::WidgetExt,
+    prelude::WidgetExtManual,
+};
+use gtk::Orientation::Vertical;
+use relm::{Relm, Widget, connect};
+use relm_derive::{Msg, widget};
+
+use self::Msg::*;
+
+pub struct Model {
+    clicked: String,
+    dragged: String,
+    gesture: Option<GestureLongPress>,
+    long_press_count: u32,
+    press_position: (f64, f64),
+    relm: Relm<Win>,
+    save_count: u32,
+    scrolled: (f64, f64),
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    KeyPress(EventKey),
+    LongPress,
+    Press(EventButton),
+    Quit,
+    Release(EventButton),
+    Scroll(EventScroll),
+}
+
+#[widget]
+impl Widget for Win {
+    fn init_view(&mut self) {
+        self.widgets.canvas.add_events(EventMask::BUTTON_PRESS_MASK | EventMask::BUTTON_RELEASE_MASK |
+            EventMask::BUTTON_MOTION_MASK | EventMask::SCROLL_MASK | EventMask::SMOOTH_SCROLL_MASK);
+        let gesture = GestureLongPress::new(&self.widgets.canvas);
+        connect!(self.model.relm, gesture, connect_pressed(_, _, _), LongPress);
+        self.model.gesture = Some(gesture);
+    }
+
+    fn model(relm: &Relm<Self>, _: ()) -> Model {
+        Model {
+            clicked: String::new(),
+            dragged: String::new(),
+            gesture: None,
+            long_press_count: 0,
+            press_position: (0.0, 0.0),
+            relm: relm.clone(),
+            save_count: 0,
+            scrolled: (0.0, 0.0),
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            KeyPress(event) => {
+                if event.state().contains(ModifierType::CONTROL_MASK) && event.keyval() == key::s {
+                    self.model.save_count += 1;
+                }
+            },
+            LongPress => self.model.long_press_count += 1,
+            Press(event) => {
+                let button =
+                    match event.button() {
+                        1 => "left",
+                        2 => "middle",
+                        3 => "right",
+                        _ => "other",
+                    };
+                let control = if event.state().contains(ModifierType::CONTROL_MASK) { "ctrl+" } else { "" };
+                self.model.clicked = format!("{}{}", control, button);
+                self.model.press_position = event.position();
+            },
+            Quit => gtk::main_quit(),
+            Release(event) => {
+                let (x, y) = event.position();
+                self.model.dragged = format!("{},{} -> {},{}", self.model.press_position.0,
+                    self.model.press_position.1, x, y);
+            },
+            Scroll(event) => {
+                let (delta_x, delta_y) = event.delta();
+                self.model.scrolled.0 += delta_x;
+                self.model.scrolled.1 += delta_y;
+            },
+        }
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                #[name="canvas"]
+                gtk::DrawingArea {
+                    can_focus: true,
+                    height_request: 100,
+                    width_request: 100,
+                    button_press_event(_, event) => (Press(event.clone()), Inhibit(false)),
+                    button_release_event(_, event) => (Release(event.clone()), Inhibit(false)),
+                    scroll_event(_, event) => (Scroll(event.clone()), Inhibit(false)),
+                },
+                #[name="click_label"]
+                gtk::Label {
+                    text: &self.model.clicked,
+                },
+                #[name="drag_label"]
+                gtk::Label {
+                    text: &self.model.dragged,
+                },
+                #[name="scroll_label"]
+                gtk::Label {
+                    text: &format!("{},{}", self.model.scrolled.0, self.model.scrolled.1),
+                },
+                #[name="long_press_label"]
+                gtk::Label {
+                    text: &self.model.long_press_count.to_string(),
+                },
+                #[name="save_label"]
+                gtk::Label {
+                    text: &self.model.save_count.to_string(),
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+            key_press_event(_, event) => (KeyPress(event.clone()), Inhibit(false)),
+        }
+    }
+}
+
+fn main() {
+    Win::run(()).expect("Win::run failed");
+}
+
+#[cfg(test)]
+mod tests {
+    use gdk::ModifierType;
+    use gtk::prelude::LabelExt;
+
+    use gtk_test::assert_text;
+    use relm_test::{
+        click,
+        click_with_modifiers,
+        drag,
+        key_combo,
+        long_press,
+        middle_click,
+        right_click,
+        scroll,
+    };
+
+    use crate::Win;
+
+    #[relm_test::test]
+    fn pointer_input() {
+        let (_component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        let canvas = &widgets.canvas;
+        let click_label = &widgets.click_label;
+        let drag_label = &widgets.drag_label;
+        let scroll_label = &widgets.scroll_label;
+        let long_press_label = &widgets.long_press_label;
+
+        click(canvas);
+        assert_text!(click_label, "left");
+
+        right_click(canvas);
+        assert_text!(click_label, "right");
+
+        middle_click(canvas);
+        assert_text!(click_label, "middle");
+
+        click_with_modifiers(canvas, ModifierType::CONTROL_MASK);
+        assert_text!(click_label, "ctrl+left");
+
+        drag(canvas, (10, 20), (60, 40));
+        assert_text!(drag_label, "10,20 -> 60,40");
+
+        scroll(canvas, 0.0, 2.0);
+        scroll(canvas, 0.0, 1.0);
+        assert_text!(scroll_label, "0,3");
+
+        assert_text!(long_press_label, "0");
+        long_press(canvas);
+        assert_text!(long_press_label, "1");
+    }
+
+    #[relm_test::test]
+    fn key_combination() {
+        let (_component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        let canvas = &widgets.canvas;
+        let save_label = &widgets.save_label;
+
+        key_combo(canvas, "s");
+        assert_text!(save_label, "0");
+
+        key_combo(canvas, "<Ctrl>s");
+        assert_text!(save_label, "1");
+    }
+}
@@ -0,0 +1,204 @@
+/*
+ * Copyright (c) 2017-2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use gtk::{
+    Inhibit,
+    prelude::ButtonExt,
+    prelude::LabelExt,
+    prelude::OrientableExt,
+    prelude::WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+use self::CounterMsg::*;
+use self::Msg::*;
+
+pub struct CounterModel {
+    counter: i32,
+}
+
+#[derive(Msg)]
+pub enum CounterMsg {
+    Increment,
+}
+
+#[widget]
+impl Widget for Counter {
+    fn init_view(&mut self) {
+        self.widgets.inc_button.set_tooltip_text(Some("Increment the counter"));
+    }
+
+    fn model() -> CounterModel {
+        CounterModel {
+            counter: 0,
+        }
+    }
+
+    fn update(&mut self, event: CounterMsg) {
+        match event {
+            Increment => self.model.counter += 1,
+        }
+    }
+
+    view! {
+        gtk::Box {
+            orientation: Vertical,
+            #[name="label"]
+            gtk::Label {
+                text: &self.model.counter.to_string(),
+            },
+            #[name="inc_button"]
+            gtk::Button {
+                clicked => Increment,
+                label: "+",
+            },
+        }
+    }
+}
+
+#[widget]
+impl Widget for Panel {
+    fn model() {
+    }
+
+    fn update(&mut self, _event: ()) {
+    }
+
+    view! {
+        // This name stays the buildable name when the parent gives another name to this widget.
+        #[name="panel_box"]
+        gtk::Box {
+            gtk::Label {
+                text: "Panel",
+            },
+        }
+    }
+}
+
+pub struct Model {
+    saved: bool,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Save,
+    Quit,
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> Model {
+        Model {
+            saved: false,
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Save => self.model.saved = true,
+            Quit => gtk::main_quit(),
+        }
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                #[name="counter1"]
+                Counter,
+                #[name="counter2"]
+                Counter,
+                #[name="panel"]
+                Panel,
+                #[style_class="suggested-action"]
+                gtk::Button {
+                    clicked => Save,
+                    label: "Save",
+                },
+                gtk::Label {
+                    text: if self.model.saved { "Saved" } else { "Not saved" },
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+fn main() {
+    Win::run(()).expect("Win::run failed");
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk::{Button, Label, atk::Role, prelude::Cast, prelude::LabelExt};
+
+    use gtk_test::assert_text;
+    use relm_test::{
+        click,
+        find_by_css_class,
+        find_by_name,
+        find_by_role,
+        find_by_text,
+        find_by_type,
+        query::find_all_by_role,
+    };
+
+    use crate::Win;
+
+    #[test]
+    fn query() {
+        let (component, _, _) = relm::init_test::<Win>(()).expect("init_test failed");
+        let window = component.widget();
+
+        let save_button: Button = find_by_text(window, "Save")
+            .and_then(|widget| widget.downcast().ok())
+            .expect("save button");
+        assert!(find_by_text(window, "Saved").is_none());
+        click(&save_button);
+        assert!(find_by_text(window, "Saved").is_some());
+        assert_eq!(find_by_css_class(window, "suggested-action"), Some(save_button.clone().upcast()));
+
+        // The widgets of the nested components are found from the root of these components.
+        let counter1 = find_by_name(window, "counter1").expect("counter1");
+        let counter2 = find_by_name(window, "counter2").expect("counter2");
+        let label1: Label = find_by_name(&counter1, "label").and_then(|widget| widget.downcast().ok()).expect("label1");
+        let label2: Label = find_by_name(&counter2, "label").and_then(|widget| widget.downcast().ok()).expect("label2");
+        let inc_button2: Button = find_by_name(&counter2, "inc_button")
+            .and_then(|widget| widget.downcast().ok())
+            .expect("inc button");
+        click(&inc_button2);
+        assert_text!(label1, 0);
+        assert_text!(label2, 1);
+
+        // The root of a component can be found by the name given in its view and by the one given
+        // by its parent.
+        let panel = find_by_name(window, "panel_box").expect("panel");
+        assert_eq!(find_by_name(window, "panel"), Some(panel.clone()));
+        assert_eq!(find_by_text(&panel, "Panel"), find_by_text(window, "Panel"));
+
+        let first_label: Label = find_by_type(window).expect("label");
+        assert_eq!(first_label, label1);
+        assert_eq!(find_by_role(window, Role::PushButton), find_by_name(&counter1, "inc_button"));
+        assert_eq!(find_all_by_role(window, Role::PushButton).len(), 3);
+    }
+}
@@ -0,0 +1,107 @@
+/*
+ * Copyright (c) 2017-2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use gtk::{
+    Inhibit,
+    prelude::ButtonExt,
+    prelude::OrientableExt,
+    prelude::WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+use self::Msg::*;
+
+#[derive(Msg)]
+pub enum Msg {
+    Quit,
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() {
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Quit => gtk::main_quit(),
+        }
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                #[name="radio1"]
+                gtk::RadioButton {
+                    label: "First",
+                },
+                // The group is set right after the widget is created, since the builder cannot set it.
+                #[name="radio2"]
+                gtk::RadioButton({ group: self.radio1 }) {
+                    label: "Second",
+                },
+                #[name="radio3"]
+                gtk::RadioButton({ group: self.radio1 }) {
+                    label: "Third",
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+fn main() {
+    Win::run(()).expect("Win::run failed");
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk::prelude::{RadioButtonExt, ToggleButtonExt};
+    use relm_test::click;
+
+    use crate::Win;
+
+    #[relm_test::test]
+    fn radio_group() {
+        let (_component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        let radio1 = &widgets.radio1;
+        let radio2 = &widgets.radio2;
+        let radio3 = &widgets.radio3;
+
+        assert_eq!(radio1.group().len(), 3);
+        assert_eq!(radio2.group(), radio1.group());
+        assert_eq!(radio3.group(), radio1.group());
+        assert!(radio1.is_active());
+        assert!(!radio2.is_active());
+        assert!(!radio3.is_active());
+
+        click(radio2);
+        assert!(!radio1.is_active());
+        assert!(radio2.is_active());
+        assert!(!radio3.is_active());
+
+        click(radio3);
+        assert!(!radio2.is_active());
+        assert!(radio3.is_active());
+    }
+}
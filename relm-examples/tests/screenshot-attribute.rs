@@ -0,0 +1,108 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::f64::consts::PI;
+
+use gtk::{
+    DrawingArea,
+    Inhibit,
+    prelude::WidgetExt,
+};
+use relm::{DrawHandler, Widget};
+use relm_derive::{Msg, widget};
+
+use self::Msg::*;
+
+pub struct Model {
+    draw_handler: DrawHandler<DrawingArea>,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Quit,
+    UpdateDrawBuffer,
+}
+
+#[widget]
+impl Widget for Win {
+    fn init_view(&mut self) {
+        self.model.draw_handler.init(&self.widgets.drawing_area);
+    }
+
+    fn model() -> Model {
+        Model {
+            draw_handler: DrawHandler::new().expect("draw handler"),
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Quit => gtk::main_quit(),
+            UpdateDrawBuffer => {
+                let context = self.model.draw_handler.get_context().expect("draw context");
+                context.set_source_rgb(1.0, 1.0, 1.0);
+                context.paint().expect("paint");
+                context.set_source_rgb(0.2, 0.4, 0.8);
+                context.arc(30.0, 30.0, 15.0, 0.0, 2.0 * PI);
+                context.fill().expect("fill");
+                context.set_source_rgb(0.8, 0.1, 0.1);
+                context.arc(65.0, 60.0, 25.0, 0.0, 2.0 * PI);
+                context.fill().expect("fill");
+                context.set_source_rgb(0.1, 0.2, 0.3);
+                context.rectangle(10.0, 70.0, 20.0, 20.0);
+                context.fill().expect("fill");
+            },
+        }
+    }
+
+    view! {
+        gtk::Window {
+            #[name="drawing_area"]
+            gtk::DrawingArea {
+                height_request: 100,
+                width_request: 100,
+                size_allocate(_, _) => UpdateDrawBuffer,
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+fn main() {
+    Win::run(()).expect("Win::run failed");
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk_test::wait;
+    use relm_test::{assert_screenshot, screenshot::Tolerance};
+
+    use crate::Win;
+
+    #[relm_test::test]
+    fn drawing_screenshot() {
+        let (_component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        // Wait for the drawing area to be allocated and for the drawing to be done.
+        wait(200);
+        // The antialiasing of the circles can be a bit different with another version of cairo.
+        assert_screenshot!("drawing", &widgets.drawing_area, Tolerance { channel: 8, pixels: 0.01 });
+    }
+}
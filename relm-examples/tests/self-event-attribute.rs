@@ -0,0 +1,123 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+use gtk::{
+    Inhibit,
+    prelude::ButtonExt,
+    prelude::EntryExt,
+    prelude::LabelExt,
+    prelude::OrientableExt,
+    prelude::WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+use self::Msg::*;
+
+pub struct Model {
+    id: u32,
+    saved: String,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Next,
+    Save(u32, String),
+    Quit,
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> Model {
+        Model {
+            id: 1,
+            saved: String::new(),
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Next => self.model.id += 1,
+            Save(id, text) => self.model.saved = format!("{}: {}", id, text),
+            Quit => gtk::main_quit(),
+        }
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                #[name="entry"]
+                gtk::Entry {
+                },
+                #[name="next_button"]
+                gtk::Button {
+                    clicked => Next,
+                    label: "Next",
+                },
+                #[name="save_button"]
+                gtk::Button {
+                    // The model attribute and the widget are read when the button is clicked.
+                    clicked => Save(self.model.id, self.widgets.entry.text().to_string()),
+                    label: "Save",
+                },
+                #[name="label"]
+                gtk::Label {
+                    text: &self.model.saved,
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+fn main() {
+    Win::run(()).expect("Win::run failed");
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk::prelude::LabelExt;
+
+    use gtk_test::assert_text;
+    use relm_test::{click, enter_keys};
+
+    use crate::Win;
+
+    #[test]
+    fn self_in_event() {
+        let (_component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        let entry = &widgets.entry;
+        let next_button = &widgets.next_button;
+        let save_button = &widgets.save_button;
+        let label = &widgets.label;
+
+        enter_keys(entry, "first");
+        click(save_button);
+        assert_text!(label, "1: first");
+
+        click(next_button);
+        click(next_button);
+        enter_keys(entry, " and third");
+        click(save_button);
+        assert_text!(label, "3: first and third");
+    }
+}
@@ -0,0 +1,164 @@
+/*
+ * Copyright (c) 2017-2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+use gtk::{
+    Inhibit,
+    prelude::ButtonExt,
+    prelude::LabelExt,
+    prelude::OrientableExt,
+    prelude::WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+use self::Msg::*;
+
+#[widget]
+impl Widget for Card {
+    fn model() -> () {
+    }
+
+    fn update(&mut self, _msg: ()) {
+    }
+
+    view! {
+        gtk::Box {
+            orientation: Vertical,
+            // The named containers are the slots of the card.
+            #[container="header"]
+            gtk::Box {
+                widget_name: "header",
+            },
+            // The children without a slot are added in the default container.
+            #[container]
+            gtk::Box {
+                widget_name: "body",
+                orientation: Vertical,
+            },
+            #[container="actions"]
+            gtk::ButtonBox {
+                widget_name: "actions",
+            },
+        }
+    }
+}
+
+#[widget]
+impl Widget for CloseButton {
+    fn model() -> () {
+    }
+
+    fn update(&mut self, _msg: ()) {
+    }
+
+    view! {
+        gtk::Button {
+            label: "Close",
+        },
+    }
+}
+
+pub struct Model {
+    counter: i32,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Increment,
+    Quit,
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> Model {
+        Model {
+            counter: 0,
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Increment => self.model.counter += 1,
+            Quit => gtk::main_quit(),
+        }
+    }
+
+    view! {
+        gtk::Window {
+            Card {
+                #[name="title"]
+                #[slot="header"]
+                gtk::Label {
+                    text: "Counter",
+                },
+                #[name="label"]
+                gtk::Label {
+                    text: &self.model.counter.to_string(),
+                },
+                #[name="inc_button"]
+                #[slot="actions"]
+                gtk::Button {
+                    clicked => Increment,
+                    label: "+",
+                },
+                #[name="close_button"]
+                #[slot="actions"]
+                CloseButton,
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+fn main() {
+    Win::run(()).expect("Win::run failed");
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk::prelude::{IsA, LabelExt, WidgetExt};
+
+    use gtk_test::assert_text;
+    use relm_test::click;
+
+    use crate::Win;
+
+    fn parent_name<W: IsA<gtk::Widget>>(widget: &W) -> String {
+        widget.parent().expect("parent").widget_name().to_string()
+    }
+
+    #[test]
+    fn slots() {
+        let (_component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        let title = &widgets.title;
+        let label = &widgets.label;
+        let inc_button = &widgets.inc_button;
+        let close_button = &widgets.close_button;
+
+        assert_eq!(parent_name(title), "header");
+        assert_eq!(parent_name(label), "body");
+        assert_eq!(parent_name(inc_button), "actions");
+        assert_eq!(parent_name(close_button), "actions");
+
+        click(inc_button);
+        assert_text!(label, 1);
+    }
+}
@@ -0,0 +1,119 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+use gtk::{
+    EditableSignals,
+    Inhibit,
+    prelude::ButtonExt,
+    prelude::EntryExt,
+    prelude::OrientableExt,
+    prelude::WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+use self::Msg::*;
+
+pub struct Model {
+    invalid: bool,
+    locked: bool,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Change(String),
+    ToggleLock,
+    Quit,
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> Model {
+        Model {
+            invalid: true,
+            locked: false,
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Change(text) => self.model.invalid = text.parse::<u32>().is_err(),
+            ToggleLock => self.model.locked = !self.model.locked,
+            Quit => gtk::main_quit(),
+        }
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                #[name="entry"]
+                #[style_class(error = self.model.invalid)]
+                gtk::Entry {
+                    changed(entry) => Change(entry.text().to_string()),
+                },
+                #[name="lock_button"]
+                #[style_class="flat"]
+                #[style_class(suggested = !self.model.locked, "destructive-action" = self.model.locked)]
+                gtk::Button {
+                    clicked => ToggleLock,
+                    label: "Lock",
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+fn main() {
+    Win::run(()).expect("Win::run failed");
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk::prelude::{StyleContextExt, WidgetExt};
+
+    use relm_test::{click, enter_keys};
+
+    use crate::Win;
+
+    #[test]
+    fn style_classes() {
+        let (_component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        let entry = &widgets.entry;
+        let lock_button = &widgets.lock_button;
+
+        assert!(entry.style_context().has_class("error"));
+        enter_keys(entry, "42");
+        assert!(!entry.style_context().has_class("error"));
+        enter_keys(entry, "a");
+        assert!(entry.style_context().has_class("error"));
+
+        let context = lock_button.style_context();
+        assert!(context.has_class("flat"));
+        assert!(context.has_class("suggested"));
+        assert!(!context.has_class("destructive-action"));
+        click(lock_button);
+        assert!(context.has_class("flat"));
+        assert!(!context.has_class("suggested"));
+        assert!(context.has_class("destructive-action"));
+    }
+}
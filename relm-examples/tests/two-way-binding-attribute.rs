@@ -0,0 +1,170 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use gtk::{
+    Inhibit,
+    prelude::ButtonExt,
+    prelude::LabelExt,
+    prelude::OrientableExt,
+    prelude::WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::Widget;
+use relm_derive::{Msg, widget};
+
+use self::Msg::*;
+
+pub struct Model {
+    greeting: String,
+    loud: bool,
+    name: String,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Greet,
+    Reset,
+    Quit,
+}
+
+#[widget]
+impl Widget for Win {
+    fn model() -> Model {
+        Model {
+            greeting: String::new(),
+            loud: false,
+            name: "World".to_string(),
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            // The name and loud attributes are kept in sync with the widgets by the bindings.
+            Greet => {
+                let greeting = format!("Hello, {}!", self.model.name);
+                self.model.greeting =
+                    if self.model.loud {
+                        greeting.to_uppercase()
+                    }
+                    else {
+                        greeting
+                    };
+            },
+            Reset => {
+                self.model.loud = false;
+                self.model.name = String::new();
+            },
+            Quit => gtk::main_quit(),
+        }
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                #[name="entry"]
+                gtk::Entry {
+                    text <=> self.model.name,
+                },
+                #[name="check_button"]
+                gtk::CheckButton {
+                    label: "Loud",
+                    active <=> self.model.loud,
+                },
+                #[name="greet_button"]
+                gtk::Button {
+                    clicked => Greet,
+                    label: "Greet",
+                },
+                #[name="reset_button"]
+                gtk::Button {
+                    clicked => Reset,
+                    label: "Reset",
+                },
+                #[name="label"]
+                gtk::Label {
+                    text: &self.model.greeting,
+                },
+                #[name="name_label"]
+                gtk::Label {
+                    text: &self.model.name,
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+fn main() {
+    Win::run(()).expect("Win::run failed");
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk::prelude::{EntryExt, LabelExt, ToggleButtonExt};
+
+    use gtk_test::{assert_text, wait};
+    use relm_test::{click, enter_keys};
+
+    use crate::Win;
+
+    #[test]
+    fn two_way_binding() {
+        let (_component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        let entry = &widgets.entry;
+        let check_button = &widgets.check_button;
+        let greet_button = &widgets.greet_button;
+        let reset_button = &widgets.reset_button;
+        let label = &widgets.label;
+
+        assert_text!(entry, "World");
+
+        click(greet_button);
+        assert_text!(label, "Hello, World!");
+
+        click(reset_button);
+        assert_text!(entry, "");
+        assert!(!check_button.is_active());
+
+        enter_keys(entry, "relm");
+        click(check_button);
+        wait(200);
+        click(greet_button);
+        assert_text!(label, "HELLO, RELM!");
+    }
+
+    #[test]
+    fn binding_updates_other_widgets() {
+        let (_component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        let entry = &widgets.entry;
+        let reset_button = &widgets.reset_button;
+        let name_label = &widgets.name_label;
+
+        assert_text!(name_label, "World");
+
+        click(reset_button);
+        assert_text!(name_label, "");
+
+        // No message is sent after typing: the label is updated by the binding itself.
+        enter_keys(entry, "relm");
+        assert_text!(name_label, "relm");
+    }
+}
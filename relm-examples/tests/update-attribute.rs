@@ -0,0 +1,146 @@
+/*
+ * Copyright (c) 2017-2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+use gtk::{
+    Inhibit,
+    prelude::ButtonExt,
+    prelude::LabelExt,
+    prelude::OrientableExt,
+    prelude::WidgetExt,
+};
+use gtk::Orientation::Vertical;
+use relm::{EventStream, Relm, Widget, connect_stream, execute};
+use relm_derive::{Msg, update, widget};
+
+use self::CounterMsg::*;
+use self::Msg::*;
+
+pub struct CounterModel {
+    relm: Relm<Counter>,
+    step: i32,
+    value: i32,
+}
+
+#[derive(Msg)]
+pub enum CounterMsg {
+    Add,
+    Changed(i32),
+}
+
+// A component without a view: the struct and the UpdateNew implementation are generated.
+#[update]
+impl Update for Counter {
+    fn model(relm: &Relm<Self>, step: i32) -> CounterModel {
+        CounterModel {
+            relm: relm.clone(),
+            step,
+            value: 0,
+        }
+    }
+
+    fn update(&mut self, event: CounterMsg) {
+        match event {
+            Add => {
+                self.model.value += self.model.step;
+                self.model.relm.stream().emit(Changed(self.model.value));
+            },
+            Changed(_) => (),
+        }
+    }
+}
+
+pub struct Model {
+    counter: EventStream<CounterMsg>,
+    value: i32,
+}
+
+#[derive(Msg)]
+pub enum Msg {
+    Increment,
+    Quit,
+    Value(i32),
+}
+
+#[widget]
+impl Widget for Win {
+    fn model(relm: &Relm<Self>, _: ()) -> Model {
+        let counter = execute::<Counter>(2);
+        connect_stream!(counter@Changed(value), relm.stream(), Value(value));
+        Model {
+            counter,
+            value: 0,
+        }
+    }
+
+    fn update(&mut self, event: Msg) {
+        match event {
+            Increment => self.model.counter.emit(Add),
+            Quit => gtk::main_quit(),
+            Value(value) => self.model.value = value,
+        }
+    }
+
+    view! {
+        gtk::Window {
+            gtk::Box {
+                orientation: Vertical,
+                #[name="inc_button"]
+                gtk::Button {
+                    clicked => Increment,
+                    label: "+",
+                },
+                #[name="label"]
+                gtk::Label {
+                    text: &self.model.value.to_string(),
+                },
+            },
+            delete_event(_, _) => (Quit, Inhibit(false)),
+        }
+    }
+}
+
+fn main() {
+    Win::run(()).expect("Win::run failed");
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk::prelude::LabelExt;
+
+    use gtk_test::assert_text;
+    use relm_test::click;
+
+    use crate::Win;
+
+    #[test]
+    fn update_attribute() {
+        let (_component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+        let inc_button = &widgets.inc_button;
+        let label = &widgets.label;
+
+        assert_text!(label, 0);
+
+        click(inc_button);
+        assert_text!(label, 2);
+
+        click(inc_button);
+        assert_text!(label, 4);
+    }
+}
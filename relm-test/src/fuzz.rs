@@ -0,0 +1,220 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Property-based testing of the `update()` method: random sequences of messages are sent to a
+//! component and invariants are checked after each message.
+//! When an invariant does not hold, the sequence is shrunk to a minimal one which still fails.
+//!
+//! This module requires the `proptest` feature.
+//!
+//! ```ignore
+//! use relm_test::fuzz::{check_updates, proptest::prelude::*};
+//!
+//! #[test]
+//! fn value_stays_in_bounds() {
+//!     let messages = prop_oneof![Just(Increment), Just(Decrement), any::<i32>().prop_map(Set)];
+//!     check_updates::<Counter, _, _>(10, messages, |counter| {
+//!         assert!(counter.model.value <= counter.model.max);
+//!     });
+//! }
+//! ```
+//!
+//! The `update()` method is called directly, without running the main loop: the subscriptions
+//! are not started and the messages that the component emits to its own stream are sent to
+//! `update()` right after the message which emitted them, before the invariant is checked.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::Rc;
+
+use gtk::prelude::*;
+use proptest::collection::vec;
+use proptest::strategy::Strategy;
+use proptest::test_runner::{Config, TestCaseError, TestError, TestRunner};
+use relm::{DisplayVariant, EventStream, Relm, Update, UpdateNew, Widget};
+
+use crate::harness::panic_message;
+
+pub use proptest;
+
+/// Maximum number of messages a component can emit to itself in response to a single message,
+/// to detect the cycles.
+const MAX_EMITTED_MESSAGES: usize = 1000;
+
+/// Options of the fuzzing functions.
+#[derive(Clone, Debug)]
+pub struct Options {
+    /// Number of random sequences to try.
+    pub cases: u32,
+    /// Maximum number of messages in a sequence.
+    pub max_messages: usize,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            cases: 256,
+            max_messages: 32,
+        }
+    }
+}
+
+/// Send random sequences of messages generated by `messages` to components created with
+/// `model_param` and call `invariant`, which should panic when it does not hold, after each
+/// message.
+///
+/// # Panics
+///
+/// Panics with the minimal failing sequence of messages if an invariant does not hold or if
+/// `update()` panics.
+pub fn check_updates<UPDATE, MESSAGES, INVARIANT>(model_param: UPDATE::ModelParam, messages: MESSAGES,
+    invariant: INVARIANT)
+    where UPDATE: UpdateNew + 'static,
+          UPDATE::ModelParam: Clone,
+          UPDATE::Msg: Clone + Debug + DisplayVariant + 'static,
+          MESSAGES: Strategy<Value=UPDATE::Msg>,
+          INVARIANT: Fn(&UPDATE),
+{
+    check_updates_with(Options::default(), model_param, messages, invariant)
+}
+
+/// Same as `check_updates()`, with custom options.
+pub fn check_updates_with<UPDATE, MESSAGES, INVARIANT>(options: Options, model_param: UPDATE::ModelParam,
+    messages: MESSAGES, invariant: INVARIANT)
+    where UPDATE: UpdateNew + 'static,
+          UPDATE::ModelParam: Clone,
+          UPDATE::Msg: Clone + Debug + DisplayVariant + 'static,
+          MESSAGES: Strategy<Value=UPDATE::Msg>,
+          INVARIANT: Fn(&UPDATE),
+{
+    run(options, messages, |relm| {
+        let model = UPDATE::model(relm, model_param.clone());
+        UPDATE::new(relm, model)
+    }, invariant, |_| ());
+}
+
+/// Same as `check_updates()`, for a widget.
+/// A view is created for every sequence and destroyed at the end of it, so this must be called
+/// from the GTK thread, for instance in a `#[relm_test::test]`.
+pub fn check_widget_updates<WIDGET, MESSAGES, INVARIANT>(model_param: WIDGET::ModelParam, messages: MESSAGES,
+    invariant: INVARIANT)
+    where WIDGET: Widget + 'static,
+          WIDGET::ModelParam: Clone,
+          WIDGET::Msg: Clone + Debug + DisplayVariant + 'static,
+          MESSAGES: Strategy<Value=WIDGET::Msg>,
+          INVARIANT: Fn(&WIDGET),
+{
+    check_widget_updates_with(Options::default(), model_param, messages, invariant)
+}
+
+/// Same as `check_widget_updates()`, with custom options.
+pub fn check_widget_updates_with<WIDGET, MESSAGES, INVARIANT>(options: Options, model_param: WIDGET::ModelParam,
+    messages: MESSAGES, invariant: INVARIANT)
+    where WIDGET: Widget + 'static,
+          WIDGET::ModelParam: Clone,
+          WIDGET::Msg: Clone + Debug + DisplayVariant + 'static,
+          MESSAGES: Strategy<Value=WIDGET::Msg>,
+          INVARIANT: Fn(&WIDGET),
+{
+    run(options, messages, |relm| {
+        let model = WIDGET::model(relm, model_param.clone());
+        let mut widget = WIDGET::view(relm, model);
+        widget.init_view();
+        widget
+    }, invariant, |widget| unsafe {
+        widget.root().upcast::<gtk::Widget>().destroy();
+    });
+}
+
+fn run<UPDATE, MESSAGES, CREATE, INVARIANT, DESTROY>(options: Options, messages: MESSAGES, create: CREATE,
+    invariant: INVARIANT, destroy: DESTROY)
+    where UPDATE: Update + 'static,
+          UPDATE::Msg: Clone + Debug + DisplayVariant + 'static,
+          MESSAGES: Strategy<Value=UPDATE::Msg>,
+          CREATE: Fn(&Relm<UPDATE>) -> UPDATE,
+          INVARIANT: Fn(&UPDATE),
+          DESTROY: Fn(UPDATE),
+{
+    let config = Config {
+        cases: options.cases,
+        // The failing sequences cannot be saved next to the test since its file is unknown here.
+        failure_persistence: None,
+        ..Config::default()
+    };
+    let mut runner = TestRunner::new(config);
+    let result = runner.run(&vec(messages, 0..=options.max_messages), |messages| {
+        let stream = EventStream::new();
+        // The observers are called synchronously when a message is emitted, unlike the callback.
+        let emitted = Rc::new(RefCell::new(VecDeque::new()));
+        {
+            let emitted = emitted.clone();
+            stream.observe(move |msg: &UPDATE::Msg| emitted.borrow_mut().push_back(msg.clone()));
+        }
+        let relm = Relm::new(&stream);
+        let mut component = create(&relm);
+        let result = run_sequence(&mut component, messages, &emitted, &invariant);
+        destroy(component);
+        result
+    });
+    match result {
+        Ok(()) => (),
+        Err(TestError::Fail(reason, messages)) =>
+            panic!("{}\nMinimal failing sequence of messages: {:#?}", reason.message(), messages),
+        Err(TestError::Abort(reason)) => panic!("Fuzzing aborted: {}", reason.message()),
+    }
+}
+
+fn run_sequence<UPDATE, INVARIANT>(component: &mut UPDATE, messages: Vec<UPDATE::Msg>,
+    emitted: &RefCell<VecDeque<UPDATE::Msg>>, invariant: &INVARIANT) -> Result<(), TestCaseError>
+    where UPDATE: Update,
+          UPDATE::Msg: Debug,
+          INVARIANT: Fn(&UPDATE),
+{
+    for (index, msg) in messages.into_iter().enumerate() {
+        let description = format!("{:?}", msg);
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            component.update(msg);
+            let mut count = 0;
+            // Do not keep the queue borrowed while calling update() since it can emit messages.
+            while let Some(msg) = pop(emitted) {
+                count += 1;
+                if count > MAX_EMITTED_MESSAGES {
+                    panic!("More than {} messages were emitted in response to a single message: is there a cycle?",
+                        MAX_EMITTED_MESSAGES);
+                }
+                component.update(msg);
+            }
+            invariant(component);
+        }));
+        if let Err(payload) = result {
+            emitted.borrow_mut().clear();
+            return Err(TestCaseError::fail(format!("Step {} failed, after the message {}: {}", index + 1,
+                description, panic_message(&*payload))));
+        }
+    }
+    Ok(())
+}
+
+fn pop<MSG>(queue: &RefCell<VecDeque<MSG>>) -> Option<MSG> {
+    queue.borrow_mut().pop_front()
+}
@@ -0,0 +1,163 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Run the tests annotated with `#[relm_test::test]` on a single GTK thread, so that they do not
+//! require `--test-threads=1`.
+//!
+//! The tests are still started by the threads of the test runner, but each one sends its body to
+//! the GTK thread and waits for it to finish there, so the tests run one after the other.
+//! After each test, the windows it created are destroyed, the pending events are processed and
+//! the settings of relm-test, like the backend and the virtual clock, are reset.
+//! A panic in a test is reported by the thread of this test, like with `#[test]`.
+//!
+//! ```ignore
+//! #[relm_test::test]
+//! fn increment() {
+//!     let (_component, _, widgets) = relm::init_test::<Win>(()).expect("init_test failed");
+//!     click(&widgets.plus_button);
+//!     assert_text!(widgets.label, 1);
+//! }
+//! ```
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::OnceLock;
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+
+use gtk::{prelude::*, Widget, Window};
+
+use crate::{backend, default_timeout, set_backend, set_default_timeout};
+
+const THREAD_NAME: &str = "relm-test-gtk";
+
+type Job = Box<dyn FnOnce() + Send>;
+
+static GTK_THREAD: OnceLock<Sender<Job>> = OnceLock::new();
+
+thread_local! {
+    static PANIC_LOCATION: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Run `test` on the GTK thread of relm-test and return its result.
+/// If `test` panics, this function panics with the same message.
+///
+/// This is called by the functions annotated with `#[relm_test::test]`.
+#[track_caller]
+pub fn run_on_gtk_thread<F, T>(test: F) -> T
+    where F: FnOnce() -> T + Send + 'static,
+          T: Send + 'static,
+{
+    let (sender, receiver) = mpsc::channel();
+    let job = Box::new(move || {
+        let result = panic::catch_unwind(AssertUnwindSafe(|| run_isolated(test)))
+            .map_err(|payload| {
+                let location = PANIC_LOCATION.with(|location| location.borrow_mut().take());
+                (panic_message(&*payload), location)
+            });
+        // The test thread cannot stop waiting, so the result can always be sent.
+        let _ = sender.send(result);
+    });
+    gtk_thread().send(job).expect("the GTK thread of relm-test stopped");
+    match receiver.recv().expect("the GTK thread of relm-test stopped") {
+        Ok(result) => result,
+        Err((message, Some(location))) => panic!("{}\n(panicked on the GTK thread at {})", message, location),
+        Err((message, None)) => panic!("{}", message),
+    }
+}
+
+fn gtk_thread() -> &'static Sender<Job> {
+    GTK_THREAD.get_or_init(|| {
+        let previous_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if thread::current().name() == Some(THREAD_NAME) {
+                // The panic is printed by the thread of the test, where the output is captured.
+                let location = info.location().map(ToString::to_string);
+                PANIC_LOCATION.with(|panic_location| *panic_location.borrow_mut() = location);
+            }
+            else {
+                previous_hook(info);
+            }
+        }));
+
+        let (sender, receiver) = mpsc::channel::<Job>();
+        thread::Builder::new()
+            .name(THREAD_NAME.to_string())
+            .spawn(move || {
+                for job in receiver {
+                    job();
+                }
+            })
+            .expect("cannot spawn the GTK thread of relm-test");
+        sender
+    })
+}
+
+fn run_isolated<F: FnOnce() -> T, T>(test: F) -> T {
+    gtk::init().expect("gtk::init failed");
+    let main_context = glib::MainContext::default();
+    let _context = main_context.acquire().expect("cannot acquire the main context");
+
+    let toplevels = Window::list_toplevels();
+    let backend = backend();
+    let timeout = default_timeout();
+
+    // The test components are dropped before the clean up since they are local to the test.
+    let result = panic::catch_unwind(AssertUnwindSafe(test));
+
+    clean_up(&toplevels);
+    set_backend(backend);
+    set_default_timeout(timeout);
+    relm::clock::disable_virtual();
+
+    match result {
+        Ok(result) => result,
+        Err(payload) => panic::resume_unwind(payload),
+    }
+}
+
+/// Destroy the windows created since `previous_toplevels` and process the pending events, so that
+/// they do not leak into the next test.
+fn clean_up(previous_toplevels: &[Widget]) {
+    for toplevel in Window::list_toplevels() {
+        if !previous_toplevels.contains(&toplevel) {
+            unsafe {
+                toplevel.destroy();
+            }
+        }
+    }
+    while gtk::events_pending() {
+        gtk::main_iteration();
+    }
+}
+
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    }
+    else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    }
+    else {
+        "Box<dyn Any>".to_string()
+    }
+}
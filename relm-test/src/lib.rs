@@ -19,120 +19,396 @@
  * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
  */
 
-use std::cell::RefCell;
+#[cfg(feature = "proptest")]
+pub mod fuzz;
+pub mod harness;
+mod message_log;
+pub mod query;
+pub mod screenshot;
+pub mod snapshot;
+mod synthetic;
+
+use std::cell::{Cell, RefCell};
+use std::env;
+use std::error::Error;
+use std::fmt;
 use std::rc::Rc;
+use std::time::{Duration, Instant};
 
+#[cfg(feature = "enigo")]
 use enigo::{Enigo, KeyboardControllable, MouseButton, MouseControllable};
+use gdk::ModifierType;
 use gdk::keys::Key;
+#[cfg(feature = "enigo")]
 use gdk::keys::constants as key;
 use glib::{IsA, Object, object::Cast};
 use gtk::{prelude::*, Inhibit, ToolButton, Widget};
-use gtk_test::{self, focus, mouse_move, run_loop, wait_for_draw};
+use gtk_test::{self, run_loop, wait_for_draw};
+#[cfg(feature = "enigo")]
+use gtk_test::{focus, mouse_move};
 use relm::StreamHandle;
 
+pub use harness::run_on_gtk_thread;
+/// Run a test on the GTK thread of relm-test: see the [`harness`](harness/index.html) module.
+pub use relm_derive::test;
+pub use message_log::MessageLog;
+pub use query::{find_by_css_class, find_by_name, find_by_role, find_by_text, find_by_type};
+pub use screenshot::screenshot;
+pub use snapshot::snapshot;
+
+/// The way the input functions, like `click()` and `enter_keys()`, send their events.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Backend {
+    /// Move the real pointer and press the real keys: this requires a focused X session.
+    #[cfg(feature = "enigo")]
+    Enigo,
+    /// Send GDK events directly to the widgets: this works under Xvfb without a window manager.
+    Synthetic,
+}
+
+thread_local! {
+    static BACKEND: Cell<Backend> = Cell::new(default_backend());
+}
+
+/// The default backend can be chosen with the `RELM_TEST_BACKEND` environment variable, which can
+/// be either `enigo` or `synthetic`.
+fn default_backend() -> Backend {
+    match env::var("RELM_TEST_BACKEND").as_deref() {
+        Ok("synthetic") => Backend::Synthetic,
+        #[cfg(feature = "enigo")]
+        Ok("enigo") => Backend::Enigo,
+        Ok(backend) => panic!("Unknown relm-test backend `{}`", backend),
+        #[cfg(feature = "enigo")]
+        Err(_) => Backend::Enigo,
+        #[cfg(not(feature = "enigo"))]
+        Err(_) => Backend::Synthetic,
+    }
+}
+
+/// Get the backend used by the input functions of the current thread.
+pub fn backend() -> Backend {
+    BACKEND.with(|backend| backend.get())
+}
+
+/// Set the backend used by the input functions of the current thread.
+pub fn set_backend(backend: Backend) {
+    BACKEND.with(|current_backend| current_backend.set(backend));
+}
+
+/// Error returned when the expected message was not received in time.
+#[derive(Clone, Debug)]
+pub struct Timeout {
+    /// The pattern of the expected message.
+    pub expected: &'static str,
+    /// The time waited for the message.
+    pub duration: Duration,
+    /// The messages received while waiting, which did not match the pattern.
+    pub received: Vec<String>,
+}
+
+impl fmt::Display for Timeout {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "Timed out after {:?} waiting for `{}`", self.duration, self.expected)?;
+        if self.received.is_empty() {
+            write!(formatter, ", no message was received.")
+        }
+        else {
+            write!(formatter, ", received: {}.", self.received.join(", "))
+        }
+    }
+}
+
+impl Error for Timeout {
+}
+
+thread_local! {
+    static DEFAULT_TIMEOUT: Cell<Duration> = Cell::new(initial_timeout());
+}
+
+/// The initial default timeout can be set in seconds with the `RELM_TEST_TIMEOUT` environment
+/// variable.
+fn initial_timeout() -> Duration {
+    match env::var("RELM_TEST_TIMEOUT") {
+        Ok(seconds) => {
+            let seconds = seconds.parse().expect("RELM_TEST_TIMEOUT should be a number of seconds");
+            Duration::from_secs_f64(seconds)
+        },
+        Err(_) => Duration::from_secs(10),
+    }
+}
+
+/// Get the timeout used by the waits of the current thread, like `Observer::wait()` and `click()`.
+pub fn default_timeout() -> Duration {
+    DEFAULT_TIMEOUT.with(|timeout| timeout.get())
+}
+
+/// Set the timeout used by the waits of the current thread.
+pub fn set_default_timeout(timeout: Duration) {
+    DEFAULT_TIMEOUT.with(|current_timeout| current_timeout.set(timeout));
+}
+
+/// Make the relm timers created afterwards on the current thread, like `relm::interval()`,
+/// `relm::timeout()` and the `.debounce(ms)` modifier, only fire when `advance()` is called.
+pub fn use_virtual_clock() {
+    relm::clock::enable_virtual();
+}
+
+/// Move the virtual clock forward by `duration`, firing the timers that become due, and process
+/// the messages they emitted.
+///
+/// # Panics
+///
+/// Panics if `use_virtual_clock()` was not called on the current thread.
+pub fn advance(duration: Duration) {
+    relm::clock::advance(duration);
+    wait_for_relm_events();
+}
+
+/// Run the main loop until `done` returns true or until `timeout` is elapsed.
+/// Return whether `done` returned true.
+fn run_loop_until<F: Fn() -> bool>(timeout: Duration, done: F) -> bool {
+    let start = Instant::now();
+    while !done() {
+        if start.elapsed() >= timeout {
+            return false;
+        }
+        run_loop();
+    }
+    true
+}
+
+/// Observer of a GTK signal, used to wait for the events sent by the input functions.
+struct SignalObserver {
+    result: Rc<Cell<bool>>,
+    signal: &'static str,
+}
+
+impl SignalObserver {
+    fn wait(&self) {
+        let timeout = default_timeout();
+        if !run_loop_until(timeout, || self.result.get()) {
+            panic!("Timed out after {:?} waiting for the signal `{}`.", timeout, self.signal);
+        }
+    }
+}
+
 // TODO: should remove the signal after wait()?
 // FIXME: remove when it's in gtk-test.
 macro_rules! gtk_observer_new {
     ($widget:expr, $signal_name:ident, |$e1:pat $(,$e:pat)*|) => {{
-        let observer = gtk_test::Observer::new();
-        let res = (*observer.get_inner()).clone();
+        let result = Rc::new(Cell::new(false));
+        let res = result.clone();
         $widget.$signal_name(move |$e1 $(,$e:expr)*| {
-            *res.borrow_mut() = true;
+            res.set(true);
         });
-        observer
+        SignalObserver {
+            result,
+            signal: stringify!($signal_name),
+        }
     }};
     ($widget:expr, $signal_name:ident, |$e1:pat $(,$e:pat)*| $block:block) => {{
-        let observer = gtk_test::Observer::new();
-        let res = (*observer.get_inner()).clone();
+        let result = Rc::new(Cell::new(false));
+        let res = result.clone();
         $widget.$signal_name(move |$e1 $(,$e)*| {
-            *res.borrow_mut() = true;
+            res.set(true);
             $block
         });
-        observer
+        SignalObserver {
+            result,
+            signal: stringify!($signal_name),
+        }
     }}
 }
 
 pub struct Observer<MSG> {
+    describe: Rc<dyn Fn(&MSG) -> String>,
+    expected: &'static str,
+    received: Rc<RefCell<Vec<String>>>,
     result: Rc<RefCell<Option<MSG>>>,
 }
 
 impl<MSG: Clone + 'static> Observer<MSG> {
     pub fn new<F: Fn(&MSG) -> bool + 'static>(stream: StreamHandle<MSG>, predicate: F) -> Self {
+        Self::with_description(stream, "the expected message", predicate, |_| "_".to_string())
+    }
+
+    /// Create an observer which can show the expected pattern and the messages received when the
+    /// wait times out.
+    /// This is used by `relm_observer_new!`.
+    pub fn with_description<D, F>(stream: StreamHandle<MSG>, expected: &'static str, predicate: F, describe: D) -> Self
+    where D: Fn(&MSG) -> String + 'static,
+          F: Fn(&MSG) -> bool + 'static,
+    {
+        let describe = Rc::new(describe);
+        let received = Rc::new(RefCell::new(vec![]));
         let result = Rc::new(RefCell::new(None));
-        let res = result.clone();
-        stream.observe(move |msg| {
-            if predicate(msg) {
-                *res.borrow_mut() = Some(msg.clone());
-            }
-        });
+        {
+            let describe = describe.clone();
+            let received = received.clone();
+            let result = result.clone();
+            stream.observe(move |msg| {
+                if predicate(msg) {
+                    *result.borrow_mut() = Some(msg.clone());
+                }
+                else {
+                    received.borrow_mut().push(describe(msg));
+                }
+            });
+        }
         Self {
+            describe,
+            expected,
+            received,
             result,
         }
     }
 
+    #[doc(hidden)]
+    pub fn describe(&self, msg: &MSG) -> String {
+        (self.describe)(msg)
+    }
+
+    /// Wait for the expected message for the default timeout.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the message is not received in time.
     pub fn wait(&self) -> MSG {
-        loop {
+        self.wait_timeout(default_timeout())
+            .unwrap_or_else(|timeout| panic!("{}", timeout))
+    }
+
+    pub fn wait_timeout(&self, timeout: Duration) -> Result<MSG, Timeout> {
+        let received = run_loop_until(timeout, || {
             if let Ok(ref result) = self.result.try_borrow() {
-                if result.is_some() {
-                    break;
-                }
+                return result.is_some();
             }
-            gtk_test::run_loop();
+            false
+        });
+        if received {
+            Ok(self.result.borrow_mut().take()
+                .expect("Message to take"))
+        }
+        else {
+            Err(Timeout {
+                expected: self.expected,
+                duration: timeout,
+                received: self.received.borrow().clone(),
+            })
         }
-        self.result.borrow_mut().take()
-            .expect("Message to take")
+    }
+}
+
+/*
+ * Show the messages with their Debug implementation when they have one, using autoref-based
+ * specialization: (&&Describe(msg)).describe() picks DescribeDebug when MSG: Debug.
+ */
+#[doc(hidden)]
+pub struct Describe<'a, MSG>(pub &'a MSG);
+
+#[doc(hidden)]
+pub trait DescribeDebug {
+    fn describe(&self) -> String;
+}
+
+impl<'a, MSG: fmt::Debug> DescribeDebug for &Describe<'a, MSG> {
+    fn describe(&self) -> String {
+        format!("{:?}", self.0)
+    }
+}
+
+#[doc(hidden)]
+pub trait DescribeOther {
+    fn describe(&self) -> String;
+}
+
+impl<'a, MSG> DescribeOther for Describe<'a, MSG> {
+    fn describe(&self) -> String {
+        "_".to_string()
     }
 }
 
 #[macro_export]
 macro_rules! relm_observer_new {
     ($component:expr, $pat:pat) => {
-        $crate::Observer::new($component.stream(), |msg|
-            if let $pat = msg {
-                true
-            }
-            else {
-                false
+        $crate::Observer::with_description($component.stream(), stringify!($pat),
+            |msg| {
+                if let $pat = msg {
+                    true
+                }
+                else {
+                    false
+                }
+            },
+            |msg| {
+                #[allow(unused_imports)]
+                use $crate::{DescribeDebug, DescribeOther};
+                (&&$crate::Describe(msg)).describe()
             }
-        );
+        )
     };
 }
 
+/// Wait for a message and destructure it.
+/// A timeout can be given, otherwise the default timeout is used:
+///
+/// ```ignore
+/// relm_observer_wait!(let Value(value) = observer, timeout = Duration::from_secs(1));
+/// ```
 #[macro_export]
 macro_rules! relm_observer_wait {
-    (let $($variant:ident)::*($name1:ident, $name2:ident $(,$rest:ident)*) = $observer:expr) => {
+    (let $($variant:ident)::*($name1:ident, $name2:ident $(,$rest:ident)*) = $observer:expr, timeout = $timeout:expr) => {
         let ($name1, $name2 $(, $rest)*) = {
-            let msg = $observer.wait();
+            let observer = &$observer;
+            let msg = observer.wait_timeout($timeout)
+                .unwrap_or_else(|timeout| panic!("{}", timeout));
             if let $($variant)::*($name1, $name2 $(, $rest)*) = msg {
                 ($name1, $name2 $(, $rest)*)
             }
             else {
-                panic!("Wrong message type.");
+                panic!("Wrong message type: expected `{}`, got `{}`.",
+                    stringify!($($variant)::*($name1, $name2 $(, $rest)*)), observer.describe(&msg));
             }
         };
     };
-    (let $($variant:ident)::*($name:ident) = $observer:expr) => {
+    (let $($variant:ident)::*($name:ident) = $observer:expr, timeout = $timeout:expr) => {
         let $name = {
-            let msg = $observer.wait();
+            let observer = &$observer;
+            let msg = observer.wait_timeout($timeout)
+                .unwrap_or_else(|timeout| panic!("{}", timeout));
             if let $($variant)::*($name) = msg {
                 $name
             }
             else {
-                panic!("Wrong message type.");
+                panic!("Wrong message type: expected `{}`, got `{}`.", stringify!($($variant)::*($name)),
+                    observer.describe(&msg));
             }
         };
     };
-    (let $($variant:ident)::* = $observer:expr) => {
+    (let $($variant:ident)::* = $observer:expr, timeout = $timeout:expr) => {
         let () = {
-            let msg = $observer.wait();
+            let observer = &$observer;
+            let msg = observer.wait_timeout($timeout)
+                .unwrap_or_else(|timeout| panic!("{}", timeout));
             if let $($variant)::* = msg {
                 ()
             }
             else {
-                panic!("Wrong message type.");
+                panic!("Wrong message type: expected `{}`, got `{}`.", stringify!($($variant)::*),
+                    observer.describe(&msg));
             }
         };
     };
+    (let $($variant:ident)::*($name1:ident, $name2:ident $(,$rest:ident)*) = $observer:expr) => {
+        $crate::relm_observer_wait!(let $($variant)::*($name1, $name2 $(, $rest)*) = $observer,
+            timeout = $crate::default_timeout())
+    };
+    (let $($variant:ident)::*($name:ident) = $observer:expr) => {
+        $crate::relm_observer_wait!(let $($variant)::*($name) = $observer, timeout = $crate::default_timeout())
+    };
+    (let $($variant:ident)::* = $observer:expr) => {
+        $crate::relm_observer_wait!(let $($variant)::* = $observer, timeout = $crate::default_timeout())
+    };
 }
 
 // FIXME: remove when it's in gtk-test.
@@ -147,10 +423,16 @@ pub fn click<W: Clone + IsA<Object> + IsA<Widget> + WidgetExt + IsA<W>>(widget:
                     Inhibit(false)
                 })
             };
-        let allocation = widget.allocation();
-        mouse_move(widget, allocation.width() / 2, allocation.height() / 2);
-        let mut enigo = Enigo::new();
-        enigo.mouse_click(MouseButton::Left);
+        match backend() {
+            #[cfg(feature = "enigo")]
+            Backend::Enigo => {
+                let allocation = widget.allocation();
+                mouse_move(widget, allocation.width() / 2, allocation.height() / 2);
+                let mut enigo = Enigo::new();
+                enigo.mouse_click(MouseButton::Left);
+            },
+            Backend::Synthetic => synthetic::click(widget),
+        }
         observer.wait();
 
         wait_for_relm_events();
@@ -159,8 +441,14 @@ pub fn click<W: Clone + IsA<Object> + IsA<Widget> + WidgetExt + IsA<W>>(widget:
 
 pub fn mouse_move_to<W: Clone + IsA<Object> + IsA<Widget> + WidgetExt + IsA<W>>(widget: &W) {
     wait_for_draw(widget, || {
-        let allocation = widget.allocation();
-        mouse_move(widget, allocation.width() / 2, allocation.height() / 2);
+        match backend() {
+            #[cfg(feature = "enigo")]
+            Backend::Enigo => {
+                let allocation = widget.allocation();
+                mouse_move(widget, allocation.width() / 2, allocation.height() / 2);
+            },
+            Backend::Synthetic => synthetic::mouse_move(widget),
+        }
 
         wait_for_relm_events();
     });
@@ -171,16 +459,148 @@ pub fn double_click<W: Clone + IsA<Object> + IsA<Widget> + WidgetExt>(widget: &W
         let observer = gtk_observer_new!(widget, connect_button_release_event, |_, _| {
             Inhibit(false)
         });
-        let allocation = widget.allocation();
-        mouse_move(widget, allocation.width() / 2, allocation.height() / 2);
-        let mut enigo = Enigo::new();
-        enigo.mouse_click(MouseButton::Left);
+        match backend() {
+            #[cfg(feature = "enigo")]
+            Backend::Enigo => {
+                let allocation = widget.allocation();
+                mouse_move(widget, allocation.width() / 2, allocation.height() / 2);
+                let mut enigo = Enigo::new();
+                enigo.mouse_click(MouseButton::Left);
+                observer.wait();
+
+                let observer = gtk_observer_new!(widget, connect_button_release_event, |_, _| {
+                    Inhibit(false)
+                });
+                enigo.mouse_click(MouseButton::Left);
+                observer.wait();
+            },
+            Backend::Synthetic => {
+                synthetic::double_click(widget);
+                observer.wait();
+            },
+        }
+
+        wait_for_relm_events();
+    });
+}
+
+pub fn right_click<W: Clone + IsA<Object> + IsA<Widget> + WidgetExt>(widget: &W) {
+    click_button(widget, synthetic::RIGHT_BUTTON, ModifierType::empty());
+}
+
+pub fn middle_click<W: Clone + IsA<Object> + IsA<Widget> + WidgetExt>(widget: &W) {
+    click_button(widget, synthetic::MIDDLE_BUTTON, ModifierType::empty());
+}
+
+/// Click with the left button while the `modifiers` keys are pressed, e.g.
+/// `ModifierType::CONTROL_MASK | ModifierType::SHIFT_MASK`.
+pub fn click_with_modifiers<W: Clone + IsA<Object> + IsA<Widget> + WidgetExt>(widget: &W, modifiers: ModifierType) {
+    click_button(widget, synthetic::LEFT_BUTTON, modifiers);
+}
+
+fn click_button<W: Clone + IsA<Object> + IsA<Widget> + WidgetExt>(widget: &W, button: u32, modifiers: ModifierType) {
+    wait_for_draw(widget, || {
+        let target = synthetic::event_target(widget.upcast_ref());
+        let observer = gtk_observer_new!(target, connect_button_press_event, |_, _| {
+            Inhibit(false)
+        });
+        match backend() {
+            #[cfg(feature = "enigo")]
+            Backend::Enigo => {
+                let allocation = widget.allocation();
+                mouse_move(widget, allocation.width() / 2, allocation.height() / 2);
+                let mut enigo = Enigo::new();
+                with_modifiers(&mut enigo, modifiers, |enigo| enigo.mouse_click(enigo_button(button)));
+            },
+            Backend::Synthetic => synthetic::click_button(widget, button, modifiers),
+        }
+        observer.wait();
+
+        wait_for_relm_events();
+    });
+}
+
+/// Press the left button long enough for a `gtk::GestureLongPress` to recognize it, then release
+/// it.
+pub fn long_press<W: Clone + IsA<Object> + IsA<Widget> + WidgetExt>(widget: &W) {
+    // The setting is the delay before the gesture is recognized: hold the button a bit longer.
+    let delay = gtk::Settings::default()
+        .map(|settings| settings.property::<u32>("gtk-long-press-time"))
+        .unwrap_or(500) + 100;
+    wait_for_draw(widget, || {
+        let target = synthetic::event_target(widget.upcast_ref());
+        let observer = gtk_observer_new!(target, connect_button_release_event, |_, _| {
+            Inhibit(false)
+        });
+        match backend() {
+            #[cfg(feature = "enigo")]
+            Backend::Enigo => {
+                let allocation = widget.allocation();
+                mouse_move(widget, allocation.width() / 2, allocation.height() / 2);
+                let mut enigo = Enigo::new();
+                enigo.mouse_down(MouseButton::Left);
+                gtk_test::wait(delay);
+                enigo.mouse_up(MouseButton::Left);
+            },
+            Backend::Synthetic => {
+                synthetic::press(widget);
+                gtk_test::wait(delay);
+                synthetic::release(widget);
+            },
+        }
         observer.wait();
 
-        let observer = gtk_observer_new!(widget, connect_button_release_event, |_, _| {
+        wait_for_relm_events();
+    });
+}
+
+/// Press the left button at the position `from`, move the pointer to `to` and release the button
+/// there.
+/// The positions are relative to the top-left corner of the widget, which must receive the button
+/// events.
+pub fn drag<W: Clone + IsA<Object> + IsA<Widget> + WidgetExt>(widget: &W, from: (i32, i32), to: (i32, i32)) {
+    wait_for_draw(widget, || {
+        let target = synthetic::event_target(widget.upcast_ref());
+        let observer = gtk_observer_new!(target, connect_button_release_event, |_, _| {
+            Inhibit(false)
+        });
+        match backend() {
+            #[cfg(feature = "enigo")]
+            Backend::Enigo => {
+                mouse_move(widget, from.0, from.1);
+                let mut enigo = Enigo::new();
+                enigo.mouse_down(MouseButton::Left);
+                for (x, y) in synthetic::drag_path(from, to) {
+                    mouse_move(widget, x, y);
+                }
+                enigo.mouse_up(MouseButton::Left);
+            },
+            Backend::Synthetic => synthetic::drag(widget, from, to),
+        }
+        observer.wait();
+
+        wait_for_relm_events();
+    });
+}
+
+/// Scroll by `delta_x` and `delta_y` in the middle of the widget.
+/// With the enigo backend, the deltas are rounded to a number of clicks of the wheel.
+pub fn scroll<W: Clone + IsA<Object> + IsA<Widget> + WidgetExt>(widget: &W, delta_x: f64, delta_y: f64) {
+    wait_for_draw(widget, || {
+        let observer = gtk_observer_new!(widget, connect_scroll_event, |_, _| {
             Inhibit(false)
         });
-        enigo.mouse_click(MouseButton::Left);
+        match backend() {
+            #[cfg(feature = "enigo")]
+            Backend::Enigo => {
+                let allocation = widget.allocation();
+                mouse_move(widget, allocation.width() / 2, allocation.height() / 2);
+                let mut enigo = Enigo::new();
+                enigo.mouse_scroll_x(delta_x.round() as i32);
+                enigo.mouse_scroll_y(delta_y.round() as i32);
+            },
+            Backend::Synthetic => synthetic::scroll(widget, delta_x, delta_y),
+        }
         observer.wait();
 
         wait_for_relm_events();
@@ -193,9 +613,18 @@ pub fn key_press<W: Clone + IsA<Object> + IsA<Widget> + WidgetExt>(widget: &W, k
         let observer = gtk_observer_new!(widget, connect_key_press_event, |_, _| {
             Inhibit(false)
         });
-        focus(widget);
-        let mut enigo = Enigo::new();
-        enigo.key_down(gdk_key_to_enigo_key(key));
+        match backend() {
+            #[cfg(feature = "enigo")]
+            Backend::Enigo => {
+                focus(widget);
+                let mut enigo = Enigo::new();
+                enigo.key_down(gdk_key_to_enigo_key(key));
+            },
+            Backend::Synthetic => {
+                synthetic::focus(widget);
+                synthetic::key_press(widget, key);
+            },
+        }
         observer.wait();
 
         wait_for_relm_events();
@@ -207,9 +636,18 @@ pub fn key_release<W: Clone + IsA<Object> + IsA<Widget> + WidgetExt>(widget: &W,
         let observer = gtk_observer_new!(widget, connect_key_release_event, |_, _| {
             Inhibit(false)
         });
-        focus(widget);
-        let mut enigo = Enigo::new();
-        enigo.key_up(gdk_key_to_enigo_key(key));
+        match backend() {
+            #[cfg(feature = "enigo")]
+            Backend::Enigo => {
+                focus(widget);
+                let mut enigo = Enigo::new();
+                enigo.key_up(gdk_key_to_enigo_key(key));
+            },
+            Backend::Synthetic => {
+                synthetic::focus(widget);
+                synthetic::key_release(widget, key);
+            },
+        }
         observer.wait();
 
         wait_for_relm_events();
@@ -221,9 +659,18 @@ pub fn enter_key<W: Clone + IsA<Object> + IsA<Widget> + WidgetExt>(widget: &W, k
         let observer = gtk_observer_new!(widget, connect_key_release_event, |_, _| {
             Inhibit(false)
         });
-        focus(widget);
-        let mut enigo = Enigo::new();
-        enigo.key_click(gdk_key_to_enigo_key(key));
+        match backend() {
+            #[cfg(feature = "enigo")]
+            Backend::Enigo => {
+                focus(widget);
+                let mut enigo = Enigo::new();
+                enigo.key_click(gdk_key_to_enigo_key(key));
+            },
+            Backend::Synthetic => {
+                synthetic::focus(widget);
+                synthetic::enter_key(widget, key);
+            },
+        }
         observer.wait();
 
         wait_for_relm_events();
@@ -232,20 +679,72 @@ pub fn enter_key<W: Clone + IsA<Object> + IsA<Widget> + WidgetExt>(widget: &W, k
 
 pub fn enter_keys<W: Clone + IsA<Object> + IsA<Widget> + WidgetExt>(widget: &W, text: &str) {
     wait_for_draw(widget, || {
-        focus(widget);
-        let mut enigo = Enigo::new();
-        for char in text.chars() {
-            let observer = gtk_observer_new!(widget, connect_key_release_event, |_, _| {
-                Inhibit(false)
-            });
-            enigo.key_sequence(&char.to_string());
-            observer.wait();
+        match backend() {
+            #[cfg(feature = "enigo")]
+            Backend::Enigo => {
+                focus(widget);
+                let mut enigo = Enigo::new();
+                for char in text.chars() {
+                    let observer = gtk_observer_new!(widget, connect_key_release_event, |_, _| {
+                        Inhibit(false)
+                    });
+                    enigo.key_sequence(&char.to_string());
+                    observer.wait();
+                }
+            },
+            Backend::Synthetic => {
+                synthetic::focus(widget);
+                for char in text.chars() {
+                    let observer = gtk_observer_new!(widget, connect_key_release_event, |_, _| {
+                        Inhibit(false)
+                    });
+                    synthetic::enter_key(widget, synthetic::char_to_key(char));
+                    observer.wait();
+                }
+            },
         }
 
         wait_for_relm_events();
     });
 }
 
+/// Press the key combination `accelerator`, in the syntax of `gtk::accelerator_parse()`, like
+/// `"<Ctrl>s"` or `"<Ctrl><Shift>Tab"`.
+/// The key events are observed on the toplevel window, so that the combination can be an
+/// accelerator of this window.
+///
+/// # Panics
+///
+/// Panics if the accelerator cannot be parsed.
+pub fn key_combo<W: Clone + IsA<Object> + IsA<Widget> + WidgetExt>(widget: &W, accelerator: &str) {
+    let (keyval, modifiers) = gtk::accelerator_parse(accelerator);
+    if keyval == 0 {
+        panic!("Invalid accelerator `{}`", accelerator);
+    }
+    let key = Key::from(keyval);
+    wait_for_draw(widget, || {
+        let toplevel = widget.toplevel().expect("toplevel window of the widget");
+        let observer = gtk_observer_new!(toplevel, connect_key_release_event, |_, _| {
+            Inhibit(false)
+        });
+        match backend() {
+            #[cfg(feature = "enigo")]
+            Backend::Enigo => {
+                focus(widget);
+                let mut enigo = Enigo::new();
+                with_modifiers(&mut enigo, modifiers, |enigo| enigo.key_click(gdk_key_to_enigo_key(key)));
+            },
+            Backend::Synthetic => {
+                synthetic::focus(widget);
+                synthetic::key_combo(widget, key, modifiers);
+            },
+        }
+        observer.wait();
+
+        wait_for_relm_events();
+    });
+}
+
 fn wait_for_relm_events() {
     gtk_test::wait(1);
     while gtk::events_pending() {
@@ -254,6 +753,46 @@ fn wait_for_relm_events() {
     }
 }
 
+/// Keep the `modifiers` keys pressed while calling `func`.
+#[cfg(feature = "enigo")]
+fn with_modifiers<F: FnOnce(&mut Enigo)>(enigo: &mut Enigo, modifiers: ModifierType, func: F) {
+    for key in modifier_keys(modifiers) {
+        enigo.key_down(key);
+    }
+    func(enigo);
+    for key in modifier_keys(modifiers).into_iter().rev() {
+        enigo.key_up(key);
+    }
+}
+
+#[cfg(feature = "enigo")]
+fn modifier_keys(modifiers: ModifierType) -> Vec<enigo::Key> {
+    let mut keys = vec![];
+    if modifiers.contains(ModifierType::CONTROL_MASK) {
+        keys.push(enigo::Key::Control);
+    }
+    if modifiers.contains(ModifierType::SHIFT_MASK) {
+        keys.push(enigo::Key::Shift);
+    }
+    if modifiers.contains(ModifierType::MOD1_MASK) {
+        keys.push(enigo::Key::Alt);
+    }
+    if modifiers.intersects(ModifierType::SUPER_MASK | ModifierType::META_MASK) {
+        keys.push(enigo::Key::Meta);
+    }
+    keys
+}
+
+#[cfg(feature = "enigo")]
+fn enigo_button(button: u32) -> MouseButton {
+    match button {
+        synthetic::MIDDLE_BUTTON => MouseButton::Middle,
+        synthetic::RIGHT_BUTTON => MouseButton::Right,
+        _ => MouseButton::Left,
+    }
+}
+
+#[cfg(feature = "enigo")]
 fn gdk_key_to_enigo_key(key: Key) -> enigo::Key {
     use enigo::Key::*;
     match key {
@@ -298,3 +837,34 @@ fn gdk_key_to_enigo_key(key: Key) -> enigo::Key {
         },
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::thread;
+    use std::time::Duration;
+
+    use crate::default_timeout;
+
+    // The default timeout is read once per thread, so each value is checked in a new thread.
+    fn default_timeout_of_new_thread() -> thread::Result<Duration> {
+        thread::spawn(default_timeout).join()
+    }
+
+    #[test]
+    fn default_timeout_from_environment() {
+        env::remove_var("RELM_TEST_TIMEOUT");
+        assert_eq!(default_timeout_of_new_thread().expect("default timeout"), Duration::from_secs(10));
+
+        env::set_var("RELM_TEST_TIMEOUT", "3");
+        assert_eq!(default_timeout_of_new_thread().expect("default timeout"), Duration::from_secs(3));
+
+        env::set_var("RELM_TEST_TIMEOUT", "0.5");
+        assert_eq!(default_timeout_of_new_thread().expect("default timeout"), Duration::from_millis(500));
+
+        env::set_var("RELM_TEST_TIMEOUT", "ten");
+        assert!(default_timeout_of_new_thread().is_err());
+
+        env::remove_var("RELM_TEST_TIMEOUT");
+    }
+}
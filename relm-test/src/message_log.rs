@@ -0,0 +1,173 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use relm::StreamHandle;
+
+use crate::run_loop_until;
+
+/// Record every message sent to one or more streams, to check whole interaction flows with
+/// `assert_sequence!`, `assert_count!` and `assert_no_message!`.
+///
+/// ```ignore
+/// let log = MessageLog::new();
+/// log.record(&streams.counter);
+/// click(&widgets.inc_button);
+/// click(&widgets.dec_button);
+/// assert_sequence!(log, [Increment, Decrement]);
+/// ```
+pub struct MessageLog<MSG> {
+    messages: Rc<RefCell<Vec<MSG>>>,
+}
+
+impl<MSG> Clone for MessageLog<MSG> {
+    fn clone(&self) -> Self {
+        Self {
+            messages: self.messages.clone(),
+        }
+    }
+}
+
+impl<MSG: Clone + 'static> Default for MessageLog<MSG> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<MSG: Clone + 'static> MessageLog<MSG> {
+    pub fn new() -> Self {
+        Self {
+            messages: Rc::new(RefCell::new(vec![])),
+        }
+    }
+
+    /// Start recording the messages of `stream`.
+    /// The messages of all the recorded streams are kept in the order they were sent.
+    pub fn record(&self, stream: &StreamHandle<MSG>) {
+        let messages = self.messages.clone();
+        stream.observe(move |msg| {
+            messages.borrow_mut().push(msg.clone());
+        });
+    }
+
+    pub fn messages(&self) -> Vec<MSG> {
+        self.messages.borrow().clone()
+    }
+
+    pub fn len(&self) -> usize {
+        self.messages.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.messages.borrow().is_empty()
+    }
+
+    /// Forget the recorded messages, to only check the messages sent afterwards.
+    pub fn clear(&self) {
+        self.messages.borrow_mut().clear();
+    }
+
+    /// Run the main loop until `predicate` returns true for the recorded messages or until `timeout`
+    /// is elapsed.
+    /// Return whether `predicate` returned true.
+    pub fn wait_until<F: Fn(&[MSG]) -> bool>(&self, timeout: Duration, predicate: F) -> bool {
+        run_loop_until(timeout, || {
+            if let Ok(messages) = self.messages.try_borrow() {
+                return predicate(&messages);
+            }
+            false
+        })
+    }
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __relm_describe_messages {
+    ($messages:expr) => {
+        $messages.iter()
+            .map(|msg| {
+                #[allow(unused_imports)]
+                use $crate::{DescribeDebug, DescribeOther};
+                (&&$crate::Describe(msg)).describe()
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+}
+
+/// Assert that the log contains exactly the messages matching the patterns, in this order.
+/// Wait for the default timeout if there are not enough messages yet.
+#[macro_export]
+macro_rules! assert_sequence {
+    ($log:expr, [$($pat:pat),* $(,)?]) => {{
+        let log = &$log;
+        let expected: &[&str] = &[$(stringify!($pat)),*];
+        log.wait_until($crate::default_timeout(), |messages| messages.len() >= expected.len());
+        let messages = log.messages();
+        let mut iter = messages.iter();
+        let matches = true $(&& matches!(iter.next(), Some($pat)))* && iter.next().is_none();
+        if !matches {
+            panic!("assertion failed: the messages do not match the sequence\n  expected: [{}]\n  received: [{}]",
+                expected.join(", "), $crate::__relm_describe_messages!(messages));
+        }
+    }};
+}
+
+/// Assert that `count` messages matching the pattern were recorded.
+/// Wait for the default timeout if there are not enough of them yet.
+#[macro_export]
+macro_rules! assert_count {
+    ($log:expr, $pat:pat, $count:expr) => {{
+        let log = &$log;
+        let expected: usize = $count;
+        log.wait_until($crate::default_timeout(), |messages|
+            messages.iter().filter(|msg| matches!(msg, $pat)).count() >= expected);
+        let messages = log.messages();
+        let count = messages.iter().filter(|msg| matches!(msg, $pat)).count();
+        if count != expected {
+            panic!("assertion failed: expected {} messages matching `{}`, got {}\n  received: [{}]",
+                expected, stringify!($pat), count, $crate::__relm_describe_messages!(messages));
+        }
+    }};
+}
+
+/// Assert that no message was recorded or, when a pattern is given, that no recorded message
+/// matches it.
+#[macro_export]
+macro_rules! assert_no_message {
+    ($log:expr) => {{
+        let messages = $log.messages();
+        if !messages.is_empty() {
+            panic!("assertion failed: expected no messages\n  received: [{}]",
+                $crate::__relm_describe_messages!(messages));
+        }
+    }};
+    ($log:expr, $pat:pat) => {{
+        let messages = $log.messages();
+        if messages.iter().any(|msg| matches!(msg, $pat)) {
+            panic!("assertion failed: expected no messages matching `{}`\n  received: [{}]",
+                stringify!($pat), $crate::__relm_describe_messages!(messages));
+        }
+    }};
+}
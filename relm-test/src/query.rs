@@ -0,0 +1,147 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Find widgets by walking the GTK hierarchy from a root widget, including the widgets of the
+//! nested relm components, so that the tests can be written against what the user sees.
+//!
+//! ```ignore
+//! let save_button: gtk::Button = find_by_text(&window, "Save")
+//!     .and_then(|widget| widget.downcast().ok())
+//!     .expect("save button");
+//! click(&save_button);
+//! ```
+
+use gtk::atk::Role;
+use gtk::{prelude::*, Button, Container, Label, MenuItem, Widget};
+
+/// Get the root and all of its descendants, in depth-first order.
+/// The internal children of the widgets, like the label of a button, are included.
+pub fn descendants<W: IsA<Widget>>(root: &W) -> Vec<Widget> {
+    let mut widgets = vec![];
+    collect_descendants(root.upcast_ref(), &mut widgets);
+    widgets
+}
+
+fn collect_descendants(widget: &Widget, widgets: &mut Vec<Widget>) {
+    widgets.push(widget.clone());
+    if let Some(container) = widget.downcast_ref::<Container>() {
+        container.forall(|child| collect_descendants(child, widgets));
+    }
+}
+
+pub fn find<W: IsA<Widget>, F: Fn(&Widget) -> bool>(root: &W, predicate: F) -> Option<Widget> {
+    descendants(root).into_iter()
+        .find(|widget| predicate(widget))
+}
+
+pub fn find_all<W: IsA<Widget>, F: Fn(&Widget) -> bool>(root: &W, predicate: F) -> Vec<Widget> {
+    descendants(root).into_iter()
+        .filter(|widget| predicate(widget))
+        .collect()
+}
+
+pub fn find_by_type<T: IsA<Widget>, W: IsA<Widget>>(root: &W) -> Option<T> {
+    find_all_by_type(root).into_iter().next()
+}
+
+pub fn find_all_by_type<T: IsA<Widget>, W: IsA<Widget>>(root: &W) -> Vec<T> {
+    descendants(root).into_iter()
+        .filter_map(|widget| widget.downcast().ok())
+        .collect()
+}
+
+/// Find a widget by the name given with the #[name] attribute in the view! macro.
+/// To find a widget of a nested relm component, search from the root of this component, as the
+/// different instances of a component use the same names:
+///
+/// ```ignore
+/// let counter = find_by_name(&window, "counter1").expect("counter");
+/// let inc_button = find_by_name(&counter, "inc_button").expect("button");
+/// ```
+pub fn find_by_name<W: IsA<Widget>>(root: &W, name: &str) -> Option<Widget> {
+    find(root, |widget| has_name(widget, name))
+}
+
+/// Check the buildable name of the widget, and the names given by the parents when it is the root
+/// of a relm widget named in its own view.
+fn has_name(widget: &Widget, name: &str) -> bool {
+    widget.buildable_name().as_deref() == Some(name) ||
+        relm::widget_names(widget).iter().any(|widget_name| widget_name == name)
+}
+
+/// Find a widget by the text shown to the user: the text of a label or the label of a button or
+/// a menu item.
+/// The button is returned instead of its inner label.
+pub fn find_by_text<W: IsA<Widget>>(root: &W, text: &str) -> Option<Widget> {
+    find(root, |widget| widget_text(widget).as_deref() == Some(text))
+}
+
+pub fn find_all_by_text<W: IsA<Widget>>(root: &W, text: &str) -> Vec<Widget> {
+    find_all(root, |widget| widget_text(widget).as_deref() == Some(text))
+}
+
+fn widget_text(widget: &Widget) -> Option<String> {
+    if let Some(button) = widget.downcast_ref::<Button>() {
+        button.label().map(Into::into)
+    }
+    else if let Some(menu_item) = widget.downcast_ref::<MenuItem>() {
+        menu_item.label().map(Into::into)
+    }
+    else if let Some(label) = widget.downcast_ref::<Label>() {
+        // Skip the label inside a button or a menu item, since its parent was already found.
+        let in_labelled_widget =
+            match label.parent() {
+                Some(parent) => parent.is::<Button>() || parent.is::<MenuItem>(),
+                None => false,
+            };
+        if in_labelled_widget {
+            None
+        }
+        else {
+            Some(label.text().into())
+        }
+    }
+    else {
+        None
+    }
+}
+
+pub fn find_by_css_class<W: IsA<Widget>>(root: &W, class: &str) -> Option<Widget> {
+    find(root, |widget| widget.style_context().has_class(class))
+}
+
+pub fn find_all_by_css_class<W: IsA<Widget>>(root: &W, class: &str) -> Vec<Widget> {
+    find_all(root, |widget| widget.style_context().has_class(class))
+}
+
+/// Find a widget by its accessible role, like `Role::PushButton` or `Role::Text`.
+pub fn find_by_role<W: IsA<Widget>>(root: &W, role: Role) -> Option<Widget> {
+    find(root, |widget| widget_role(widget) == Some(role))
+}
+
+pub fn find_all_by_role<W: IsA<Widget>>(root: &W, role: Role) -> Vec<Widget> {
+    find_all(root, |widget| widget_role(widget) == Some(role))
+}
+
+fn widget_role(widget: &Widget) -> Option<Role> {
+    widget.accessible()
+        .map(|accessible| gtk::atk::prelude::AtkObjectExt::role(&accessible))
+}
@@ -0,0 +1,354 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Render the widgets in images to compare them with reference images, for the widgets whose
+//! content cannot be checked with text assertions, like the drawing areas.
+
+use std::env;
+use std::fmt;
+use std::fs::{self, File};
+use std::path::Path;
+
+use cairo::{Context, Format, ImageSurface};
+use gtk::{prelude::*, Widget};
+
+use crate::snapshot::UPDATE_SNAPSHOTS_VAR;
+
+/// Render the widget and its children in an image of the size of the widget.
+/// The widget must be realized, which is the case after `relm::init_test()`.
+pub fn screenshot<W: IsA<Widget>>(widget: &W) -> ImageSurface {
+    let allocation = widget.allocation();
+    let surface = ImageSurface::create(Format::ARgb32, allocation.width(), allocation.height())
+        .expect("cannot create the screenshot surface");
+    {
+        let context = Context::new(&surface).expect("cannot create the screenshot context");
+        widget.draw(&context);
+    }
+    surface.flush();
+    surface
+}
+
+pub fn save_png(surface: &ImageSurface, path: &Path) {
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory).expect("cannot create the screenshot directory");
+    }
+    let mut file = File::create(path).expect("cannot create the png file");
+    surface.write_to_png(&mut file).expect("cannot write the png file");
+}
+
+pub fn load_png(path: &Path) -> ImageSurface {
+    let mut file = File::open(path).expect("cannot open the png file");
+    ImageSurface::create_from_png(&mut file).expect("cannot read the png file")
+}
+
+/// The differences allowed between two images, to ignore the small rendering differences, like
+/// antialiasing.
+#[derive(Clone, Copy, Debug)]
+pub struct Tolerance {
+    /// The maximum difference of a color channel for the pixels to be considered the same.
+    pub channel: u8,
+    /// The ratio of pixels, between 0 and 1, which can be different.
+    pub pixels: f64,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self {
+            channel: 2,
+            pixels: 0.0,
+        }
+    }
+}
+
+/// The result of a failed comparison.
+pub struct Difference {
+    pub different_pixels: usize,
+    pub total_pixels: usize,
+    /// An image with the different pixels in red over a faded version of the expected image.
+    /// It is `None` when the sizes of the images are different.
+    pub diff: Option<ImageSurface>,
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        if self.diff.is_none() {
+            write!(formatter, "the images have different sizes")
+        }
+        else {
+            write!(formatter, "{} of {} pixels are different", self.different_pixels, self.total_pixels)
+        }
+    }
+}
+
+/// Compare two images, pixel by pixel.
+pub fn compare(actual: &ImageSurface, expected: &ImageSurface, tolerance: Tolerance) -> Result<(), Difference> {
+    let width = expected.width();
+    let height = expected.height();
+    let total_pixels = (width * height) as usize;
+    if actual.width() != width || actual.height() != height {
+        return Err(Difference {
+            different_pixels: total_pixels,
+            total_pixels,
+            diff: None,
+        });
+    }
+
+    let actual_pixels = pixels(actual);
+    let expected_pixels = pixels(expected);
+    let mut diff_pixels = Vec::with_capacity(expected_pixels.len());
+    let mut different_pixels = 0;
+    for (actual, expected) in actual_pixels.iter().zip(&expected_pixels) {
+        let same = actual.iter().zip(expected)
+            .all(|(&actual, &expected)| (actual as i16 - expected as i16).unsigned_abs() <= tolerance.channel as u16);
+        if same {
+            // Faded expected pixel.
+            let [b, g, r, _] = *expected;
+            diff_pixels.push([b / 4 + 191, g / 4 + 191, r / 4 + 191, 255]);
+        }
+        else {
+            different_pixels += 1;
+            diff_pixels.push([0, 0, 255, 255]);
+        }
+    }
+
+    if different_pixels as f64 <= tolerance.pixels * total_pixels as f64 {
+        Ok(())
+    }
+    else {
+        Err(Difference {
+            different_pixels,
+            total_pixels,
+            diff: Some(image_from_pixels(width, height, &diff_pixels)),
+        })
+    }
+}
+
+/// Get the pixels of the image in the ARGB32 format, as bytes in native-endian order.
+fn pixels(surface: &ImageSurface) -> Vec<[u8; 4]> {
+    let width = surface.width();
+    let height = surface.height();
+    let image = ImageSurface::create(Format::ARgb32, width, height)
+        .expect("cannot create the surface");
+    {
+        // Copy the surface to access its data and to convert it to ARGB32.
+        let context = Context::new(&image).expect("cannot create the context");
+        context.set_source_surface(surface, 0.0, 0.0).expect("cannot set the source surface");
+        context.paint().expect("cannot paint the surface");
+    }
+    let stride = image.stride() as usize;
+    let data = image.take_data().expect("cannot get the surface data");
+    let mut pixels = Vec::with_capacity((width * height) as usize);
+    for row in data.chunks(stride) {
+        for pixel in row[..width as usize * 4].chunks(4) {
+            pixels.push([pixel[0], pixel[1], pixel[2], pixel[3]]);
+        }
+    }
+    pixels
+}
+
+fn image_from_pixels(width: i32, height: i32, pixels: &[[u8; 4]]) -> ImageSurface {
+    let stride = Format::ARgb32.stride_for_width(width as u32).expect("stride");
+    let mut data = vec![0; stride as usize * height as usize];
+    for (row, pixels) in data.chunks_mut(stride as usize).zip(pixels.chunks(width as usize)) {
+        for (pixel, bytes) in pixels.iter().zip(row.chunks_mut(4)) {
+            bytes.copy_from_slice(pixel);
+        }
+    }
+    ImageSurface::create_for_data(data, Format::ARgb32, width, height, stride)
+        .expect("cannot create the diff image")
+}
+
+/// Compare the image with the reference image at `path`.
+/// The reference image is written instead when the environment variable
+/// `RELM_TEST_UPDATE_SNAPSHOTS` is set.
+///
+/// # Panics
+///
+/// Panics if the reference image does not exist or if the images are different. In the latter
+/// case, the actual image and the diff image are written next to the reference image, with the
+/// `.actual.png` and `.diff.png` extensions.
+pub fn assert_screenshot_file(path: &Path, actual: &ImageSurface, tolerance: Tolerance) {
+    compare_screenshot_file(path, actual, tolerance, env::var_os(UPDATE_SNAPSHOTS_VAR).is_some());
+}
+
+fn compare_screenshot_file(path: &Path, actual: &ImageSurface, tolerance: Tolerance, update: bool) {
+    if update {
+        save_png(actual, path);
+        return;
+    }
+    if !path.exists() {
+        panic!("screenshot `{}` does not exist. Run the tests with {}=1 to create it.", path.display(),
+            UPDATE_SNAPSHOTS_VAR);
+    }
+    let expected = load_png(path);
+    if let Err(difference) = compare(actual, &expected, tolerance) {
+        let actual_path = path.with_extension("actual.png");
+        save_png(actual, &actual_path);
+        let mut message = format!("screenshot `{}` does not match: {}.\nActual image: {}", path.display(),
+            difference, actual_path.display());
+        if let Some(ref diff) = difference.diff {
+            let diff_path = path.with_extension("diff.png");
+            save_png(diff, &diff_path);
+            message.push_str(&format!("\nDiff image: {}", diff_path.display()));
+        }
+        panic!("{}\nRun the tests with {}=1 to update the screenshot.", message, UPDATE_SNAPSHOTS_VAR);
+    }
+}
+
+/// Compare the screenshot of a widget with the reference image `tests/screenshots/<name>.png` of the
+/// crate, optionally with a tolerance:
+///
+/// ```ignore
+/// assert_screenshot!("circles", &widgets.drawing_area);
+/// assert_screenshot!("circles", &widgets.drawing_area, Tolerance { channel: 8, pixels: 0.01 });
+/// ```
+#[macro_export]
+macro_rules! assert_screenshot {
+    ($name:expr, $widget:expr) => {
+        $crate::assert_screenshot!($name, $widget, $crate::screenshot::Tolerance::default())
+    };
+    ($name:expr, $widget:expr, $tolerance:expr) => {
+        $crate::screenshot::assert_screenshot_file(
+            &::std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("tests")
+                .join("screenshots")
+                .join(format!("{}.png", $name)),
+            &$crate::screenshot::screenshot($widget),
+            $tolerance)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+    use std::panic::{self, AssertUnwindSafe};
+    use std::path::PathBuf;
+    use std::process;
+
+    use cairo::{Context, Format, ImageSurface};
+
+    use crate::harness::panic_message;
+    use super::{Tolerance, compare, compare_screenshot_file, load_png, pixels};
+
+    const BLUE: (f64, f64, f64) = (0.2, 0.4, 0.8);
+    const RED: [u8; 4] = [0, 0, 255, 255];
+
+    /// Create an image of the color `background` with a square of 10x10 pixels of the color
+    /// `square` at the top left corner.
+    fn image(width: i32, height: i32, background: (f64, f64, f64), square: (f64, f64, f64)) -> ImageSurface {
+        let surface = ImageSurface::create(Format::ARgb32, width, height).expect("create surface");
+        {
+            let context = Context::new(&surface).expect("create context");
+            context.set_source_rgb(background.0, background.1, background.2);
+            context.paint().expect("paint");
+            context.set_source_rgb(square.0, square.1, square.2);
+            context.rectangle(0.0, 0.0, 10.0, 10.0);
+            context.fill().expect("fill");
+        }
+        surface
+    }
+
+    fn screenshot_path(name: &str) -> PathBuf {
+        env::temp_dir()
+            .join(format!("relm-test-{}", process::id()))
+            .join(format!("{}.png", name))
+    }
+
+    #[test]
+    fn identical_images() {
+        let actual = image(100, 50, BLUE, BLUE);
+        let expected = image(100, 50, BLUE, BLUE);
+        assert!(compare(&actual, &expected, Tolerance { channel: 0, pixels: 0.0 }).is_ok());
+    }
+
+    #[test]
+    fn images_within_tolerance() {
+        let expected = image(100, 50, BLUE, BLUE);
+        // A difference of 1 in two channels of the background.
+        let actual = image(100, 50, (0.2 + 1.0 / 255.0, 0.4, 0.8 - 1.0 / 255.0), BLUE);
+        assert!(compare(&actual, &expected, Tolerance::default()).is_ok());
+        let difference = compare(&actual, &expected, Tolerance { channel: 0, pixels: 0.0 })
+            .expect_err("difference");
+        assert_eq!(difference.different_pixels, 4900);
+
+        // 100 of the 5000 pixels are different.
+        let actual = image(100, 50, BLUE, (1.0, 1.0, 1.0));
+        assert!(compare(&actual, &expected, Tolerance { channel: 2, pixels: 0.02 }).is_ok());
+    }
+
+    #[test]
+    fn images_out_of_tolerance() {
+        let expected = image(100, 50, BLUE, BLUE);
+        let actual = image(100, 50, BLUE, (1.0, 1.0, 1.0));
+        let difference = compare(&actual, &expected, Tolerance { channel: 2, pixels: 0.01 })
+            .expect_err("difference");
+        assert_eq!(difference.different_pixels, 100);
+        assert_eq!(difference.total_pixels, 5000);
+        assert_eq!(difference.to_string(), "100 of 5000 pixels are different");
+        let diff = pixels(difference.diff.as_ref().expect("diff image"));
+        assert_eq!(diff[0], RED);
+        assert_eq!(diff[9 * 100 + 9], RED);
+        assert_ne!(diff[10], RED);
+
+        let path = screenshot_path("out-of-tolerance");
+        compare_screenshot_file(&path, &expected, Tolerance::default(), true);
+        let payload = panic::catch_unwind(AssertUnwindSafe(|| {
+            compare_screenshot_file(&path, &actual, Tolerance::default(), false)
+        })).expect_err("the screenshots should differ");
+        let actual_path = path.with_extension("actual.png");
+        let diff_path = path.with_extension("diff.png");
+        assert_eq!(panic_message(&*payload), format!("screenshot `{}` does not match: 100 of 5000 pixels are different.
+Actual image: {}
+Diff image: {}
+Run the tests with RELM_TEST_UPDATE_SNAPSHOTS=1 to update the screenshot.",
+            path.display(), actual_path.display(), diff_path.display()));
+        assert_eq!(pixels(&load_png(&actual_path)), pixels(&actual));
+        assert_eq!(pixels(&load_png(&diff_path))[0], RED);
+        // The reference image is not modified.
+        assert_eq!(pixels(&load_png(&path)), pixels(&expected));
+        for path in [path, actual_path, diff_path] {
+            fs::remove_file(path).expect("remove screenshot");
+        }
+    }
+
+    #[test]
+    fn images_of_different_sizes() {
+        let expected = image(100, 50, BLUE, BLUE);
+        let actual = image(50, 100, BLUE, BLUE);
+        let difference = compare(&actual, &expected, Tolerance { channel: 255, pixels: 1.0 })
+            .expect_err("difference");
+        assert!(difference.diff.is_none());
+        assert_eq!(difference.to_string(), "the images have different sizes");
+    }
+
+    #[test]
+    fn missing_screenshot() {
+        let path = screenshot_path("missing");
+        let actual = image(100, 50, BLUE, BLUE);
+        let payload = panic::catch_unwind(AssertUnwindSafe(|| {
+            compare_screenshot_file(&path, &actual, Tolerance::default(), false)
+        })).expect_err("the screenshot should be missing");
+        assert_eq!(panic_message(&*payload), format!("screenshot `{}` does not exist. Run the tests with RELM_TEST_UPDATE_SNAPSHOTS=1 to create it.",
+            path.display()));
+        assert!(!path.exists());
+    }
+}
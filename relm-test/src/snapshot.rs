@@ -0,0 +1,338 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Serialize the widget hierarchy in a text format to compare it with golden files, in order to
+//! catch the regressions in the views.
+//!
+//! Each line contains the type of a widget, its name from the #[name] attribute, its main
+//! properties and its child properties between brackets:
+//!
+//! ```text
+//! GtkWindow #window visible=true sensitive=true
+//!   GtkBox visible=true sensitive=true
+//!     GtkLabel label="0" visible=true sensitive=true [expand=false fill=true pack-type=GTK_PACK_START padding=0 position=0]
+//! ```
+
+use std::env;
+use std::ffi::CStr;
+use std::fmt::Write as FmtWrite;
+use std::fs;
+use std::os::raw::c_uint;
+use std::path::Path;
+
+use glib::{gobject_ffi, object::ObjectType};
+use gtk::{
+    prelude::*,
+    Button,
+    CheckMenuItem,
+    Container,
+    Entry,
+    Label,
+    MenuItem,
+    Switch,
+    ToggleButton,
+    Widget,
+};
+
+extern "C" {
+    // Missing from gtk-sys.
+    fn gtk_container_class_list_child_properties(cclass: *mut gobject_ffi::GObjectClass,
+        n_properties: *mut c_uint) -> *mut *mut gobject_ffi::GParamSpec;
+}
+
+/// Set this environment variable to write the current snapshots to the golden files instead of
+/// comparing them.
+pub const UPDATE_SNAPSHOTS_VAR: &str = "RELM_TEST_UPDATE_SNAPSHOTS";
+
+/// Serialize the widget and its descendants, one widget per line.
+pub fn snapshot<W: IsA<Widget>>(root: &W) -> String {
+    let mut snapshot = String::new();
+    write_widget(&mut snapshot, root.upcast_ref(), None, 0);
+    snapshot
+}
+
+fn write_widget(snapshot: &mut String, widget: &Widget, parent: Option<&Container>, depth: usize) {
+    let _ = write!(snapshot, "{}{}", "  ".repeat(depth), widget.type_().name());
+    if let Some(name) = widget.buildable_name() {
+        let _ = write!(snapshot, " #{}", name);
+    }
+    for (name, value) in properties(widget) {
+        let _ = write!(snapshot, " {}={}", name, value);
+    }
+    if let Some(parent) = parent {
+        let child_properties = child_properties(parent, widget);
+        if !child_properties.is_empty() {
+            let child_properties: Vec<_> = child_properties.iter()
+                .map(|(name, value)| format!("{}={}", name, value))
+                .collect();
+            let _ = write!(snapshot, " [{}]", child_properties.join(" "));
+        }
+    }
+    snapshot.push('\n');
+
+    if let Some(container) = widget.downcast_ref::<Container>() {
+        // Internal children are skipped: they depend on the version of GTK.
+        for child in container.children() {
+            write_widget(snapshot, &child, Some(container), depth + 1);
+        }
+    }
+}
+
+fn properties(widget: &Widget) -> Vec<(&'static str, String)> {
+    let mut properties = vec![];
+    if let Some(label) = widget.downcast_ref::<Label>() {
+        properties.push(("label", format!("{:?}", label.label().as_str())));
+    }
+    else if let Some(button) = widget.downcast_ref::<Button>() {
+        if let Some(label) = button.label() {
+            properties.push(("label", format!("{:?}", label.as_str())));
+        }
+    }
+    else if let Some(menu_item) = widget.downcast_ref::<MenuItem>() {
+        if let Some(label) = menu_item.label() {
+            properties.push(("label", format!("{:?}", label.as_str())));
+        }
+    }
+    if let Some(entry) = widget.downcast_ref::<Entry>() {
+        properties.push(("text", format!("{:?}", entry.text().as_str())));
+    }
+    let active =
+        if let Some(toggle_button) = widget.downcast_ref::<ToggleButton>() {
+            Some(toggle_button.is_active())
+        }
+        else if let Some(switch) = widget.downcast_ref::<Switch>() {
+            Some(switch.is_active())
+        }
+        else {
+            widget.downcast_ref::<CheckMenuItem>()
+                .map(|check_menu_item| check_menu_item.is_active())
+        };
+    if let Some(active) = active {
+        properties.push(("active", active.to_string()));
+    }
+    properties.push(("visible", widget.is_visible().to_string()));
+    properties.push(("sensitive", widget.is_sensitive().to_string()));
+    properties
+}
+
+fn child_properties(container: &Container, child: &Widget) -> Vec<(String, String)> {
+    let mut properties = vec![];
+    unsafe {
+        let class = (*(container.as_ptr() as *mut gobject_ffi::GTypeInstance)).g_class as *mut gobject_ffi::GObjectClass;
+        let mut count = 0;
+        let param_specs = gtk_container_class_list_child_properties(class, &mut count);
+        for i in 0..count as usize {
+            let name = CStr::from_ptr((**param_specs.add(i)).name).to_string_lossy().into_owned();
+            let value = container.child_property_value(child, &name);
+            // The booleans are written like the widget properties instead of as TRUE/FALSE.
+            let value =
+                match value.get::<bool>() {
+                    Ok(value) => value.to_string(),
+                    Err(_) => value.transform::<String>().ok()
+                        .and_then(|value| value.get::<Option<String>>().ok().flatten())
+                        .unwrap_or_else(|| "?".to_string()),
+                };
+            properties.push((name, value));
+        }
+        glib::ffi::g_free(param_specs as *mut _);
+    }
+    properties.sort();
+    properties
+}
+
+/// Compare the snapshot with the golden file at `path`.
+/// The golden file is written instead when the environment variable `RELM_TEST_UPDATE_SNAPSHOTS`
+/// is set.
+///
+/// # Panics
+///
+/// Panics with the differing lines if the snapshot does not match the golden file, or if the
+/// golden file does not exist.
+pub fn assert_snapshot_file(path: &Path, snapshot: &str) {
+    compare_snapshot_file(path, snapshot, env::var_os(UPDATE_SNAPSHOTS_VAR).is_some());
+}
+
+fn compare_snapshot_file(path: &Path, snapshot: &str, update: bool) {
+    if update {
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory).expect("cannot create the snapshot directory");
+        }
+        fs::write(path, snapshot).expect("cannot write the snapshot");
+        return;
+    }
+    if !path.exists() {
+        panic!("snapshot `{}` does not exist. Run the tests with {}=1 to create it.", path.display(),
+            UPDATE_SNAPSHOTS_VAR);
+    }
+    let expected = fs::read_to_string(path).expect("cannot read the snapshot");
+    if expected != snapshot {
+        let mut message = format!("snapshot `{}` does not match:\n", path.display());
+        let expected_lines: Vec<_> = expected.lines().collect();
+        let actual_lines: Vec<_> = snapshot.lines().collect();
+        for (prefix, line) in diff_lines(&expected_lines, &actual_lines) {
+            let _ = writeln!(message, "{} {}", prefix, line);
+        }
+        let _ = write!(message, "Run the tests with {}=1 to update the snapshot.", UPDATE_SNAPSHOTS_VAR);
+        panic!("{}", message);
+    }
+}
+
+/// Diff the lines with their longest common subsequence, so that an inserted or removed widget
+/// only shows this widget as different.
+/// The lines are prefixed with ` ` when they are in both, `-` when they are only expected and `+`
+/// when they are only in the actual snapshot.
+fn diff_lines<'a>(expected: &[&'a str], actual: &[&'a str]) -> Vec<(char, &'a str)> {
+    // lengths[i][j] is the length of the longest common subsequence of expected[i..] and actual[j..].
+    let mut lengths = vec![vec![0; actual.len() + 1]; expected.len() + 1];
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            lengths[i][j] =
+                if expected[i] == actual[j] {
+                    lengths[i + 1][j + 1] + 1
+                }
+                else {
+                    lengths[i + 1][j].max(lengths[i][j + 1])
+                };
+        }
+    }
+    let mut lines = vec![];
+    let (mut i, mut j) = (0, 0);
+    while i < expected.len() && j < actual.len() {
+        if expected[i] == actual[j] {
+            lines.push((' ', expected[i]));
+            i += 1;
+            j += 1;
+        }
+        else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            lines.push(('-', expected[i]));
+            i += 1;
+        }
+        else {
+            lines.push(('+', actual[j]));
+            j += 1;
+        }
+    }
+    lines.extend(expected[i..].iter().map(|line| ('-', *line)));
+    lines.extend(actual[j..].iter().map(|line| ('+', *line)));
+    lines
+}
+
+/// Compare the snapshot of a widget with the golden file `tests/snapshots/<name>.snap` of the
+/// crate:
+///
+/// ```ignore
+/// assert_snapshot!("counter", component.widget());
+/// ```
+#[macro_export]
+macro_rules! assert_snapshot {
+    ($name:expr, $widget:expr) => {
+        $crate::snapshot::assert_snapshot_file(
+            &::std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("tests")
+                .join("snapshots")
+                .join(format!("{}.snap", $name)),
+            &$crate::snapshot::snapshot($widget))
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+    use std::panic;
+    use std::path::{Path, PathBuf};
+    use std::process;
+
+    use crate::harness::panic_message;
+    use super::compare_snapshot_file;
+
+    const SNAPSHOT: &str = "GtkBox visible=true sensitive=true
+  GtkLabel label=\"0\" visible=true sensitive=true
+  GtkButton label=\"+\" visible=true sensitive=true
+";
+
+    fn snapshot_path(name: &str) -> PathBuf {
+        env::temp_dir()
+            .join(format!("relm-test-{}", process::id()))
+            .join(format!("{}.snap", name))
+    }
+
+    fn compare_panic_message(path: &Path, snapshot: &str) -> String {
+        let payload = panic::catch_unwind(|| compare_snapshot_file(path, snapshot, false))
+            .expect_err("the snapshots should differ");
+        panic_message(&*payload)
+    }
+
+    #[test]
+    fn matching_snapshot() {
+        let path = snapshot_path("matching");
+        compare_snapshot_file(&path, SNAPSHOT, true);
+        assert_eq!(fs::read_to_string(&path).expect("read snapshot"), SNAPSHOT);
+        compare_snapshot_file(&path, SNAPSHOT, false);
+        fs::remove_file(&path).expect("remove snapshot");
+    }
+
+    #[test]
+    fn mismatching_snapshot() {
+        let path = snapshot_path("mismatching");
+        compare_snapshot_file(&path, SNAPSHOT, true);
+        let snapshot = SNAPSHOT.replace("label=\"0\"", "label=\"1\"");
+        let message = compare_panic_message(&path, &snapshot);
+        assert_eq!(message, format!("snapshot `{}` does not match:
+  GtkBox visible=true sensitive=true
+-   GtkLabel label=\"0\" visible=true sensitive=true
++   GtkLabel label=\"1\" visible=true sensitive=true
+    GtkButton label=\"+\" visible=true sensitive=true
+Run the tests with RELM_TEST_UPDATE_SNAPSHOTS=1 to update the snapshot.", path.display()));
+        // The golden file is not modified.
+        assert_eq!(fs::read_to_string(&path).expect("read snapshot"), SNAPSHOT);
+        fs::remove_file(&path).expect("remove snapshot");
+    }
+
+    #[test]
+    fn inserted_line() {
+        let path = snapshot_path("inserted");
+        compare_snapshot_file(&path, SNAPSHOT, true);
+        let snapshot = "GtkBox visible=true sensitive=true
+  GtkLabel label=\"0\" visible=true sensitive=true
+  GtkSeparator visible=true sensitive=true
+  GtkButton label=\"+\" visible=true sensitive=true
+";
+        // Only the inserted line is shown as different.
+        let message = compare_panic_message(&path, snapshot);
+        assert_eq!(message, format!("snapshot `{}` does not match:
+  GtkBox visible=true sensitive=true
+    GtkLabel label=\"0\" visible=true sensitive=true
++   GtkSeparator visible=true sensitive=true
+    GtkButton label=\"+\" visible=true sensitive=true
+Run the tests with RELM_TEST_UPDATE_SNAPSHOTS=1 to update the snapshot.", path.display()));
+        fs::remove_file(&path).expect("remove snapshot");
+    }
+
+    #[test]
+    fn missing_snapshot() {
+        let path = snapshot_path("missing");
+        let message = compare_panic_message(&path, SNAPSHOT);
+        assert_eq!(message, format!("snapshot `{}` does not exist. Run the tests with RELM_TEST_UPDATE_SNAPSHOTS=1 to create it.",
+            path.display()));
+        assert!(!path.exists());
+    }
+}
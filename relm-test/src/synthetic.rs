@@ -0,0 +1,285 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Input backend which sends the GDK events directly to the widgets, instead of moving the real
+//! pointer and pressing the real keys: it does not need a focused X session.
+
+use std::ptr;
+
+use gdk::{EventType, ModifierType, ScrollDirection, Window, ffi};
+use gdk::keys::Key;
+use gdk::keys::constants as key;
+use glib::{IsA, object::Cast};
+use glib::translate::{FromGlibPtrFull, IntoGlib, ToGlibPtr};
+use gtk::{prelude::*, Bin, ToolButton, Widget};
+
+pub const LEFT_BUTTON: u32 = 1;
+pub const MIDDLE_BUTTON: u32 = 2;
+pub const RIGHT_BUTTON: u32 = 3;
+
+/// Number of motion events sent between the start and the end of a drag.
+const DRAG_STEPS: i32 = 5;
+
+pub fn click<W: IsA<Widget>>(widget: &W) {
+    click_button(widget, LEFT_BUTTON, ModifierType::empty());
+}
+
+/// Click with `button` while the `modifiers` keys are pressed.
+pub fn click_button<W: IsA<Widget>>(widget: &W, button: u32, modifiers: ModifierType) {
+    let widget = event_target(widget.upcast_ref());
+    let position = center(&widget);
+    button_event(&widget, EventType::ButtonPress, button, modifiers, position);
+    button_event(&widget, EventType::ButtonRelease, button, modifiers | button_mask(button), position);
+}
+
+pub fn double_click<W: IsA<Widget>>(widget: &W) {
+    let widget = event_target(widget.upcast_ref());
+    let position = center(&widget);
+    let pressed = ModifierType::BUTTON1_MASK;
+    button_event(&widget, EventType::ButtonPress, LEFT_BUTTON, ModifierType::empty(), position);
+    button_event(&widget, EventType::ButtonRelease, LEFT_BUTTON, pressed, position);
+    button_event(&widget, EventType::ButtonPress, LEFT_BUTTON, ModifierType::empty(), position);
+    button_event(&widget, EventType::DoubleButtonPress, LEFT_BUTTON, ModifierType::empty(), position);
+    button_event(&widget, EventType::ButtonRelease, LEFT_BUTTON, pressed, position);
+}
+
+/// Press the left button in the middle of the widget, without releasing it.
+pub fn press<W: IsA<Widget>>(widget: &W) {
+    let widget = event_target(widget.upcast_ref());
+    let position = center(&widget);
+    button_event(&widget, EventType::ButtonPress, LEFT_BUTTON, ModifierType::empty(), position);
+}
+
+/// Release the left button pressed by `press()`.
+pub fn release<W: IsA<Widget>>(widget: &W) {
+    let widget = event_target(widget.upcast_ref());
+    let position = center(&widget);
+    button_event(&widget, EventType::ButtonRelease, LEFT_BUTTON, ModifierType::BUTTON1_MASK, position);
+}
+
+/// Press the left button at `from`, move the pointer to `to` and release the button there.
+/// The positions are relative to the widget.
+pub fn drag<W: IsA<Widget>>(widget: &W, from: (i32, i32), to: (i32, i32)) {
+    let widget = event_target(widget.upcast_ref());
+    let pressed = ModifierType::BUTTON1_MASK;
+    button_event(&widget, EventType::ButtonPress, LEFT_BUTTON, ModifierType::empty(), from);
+    for position in drag_path(from, to) {
+        motion_event(&widget, pressed, position);
+    }
+    button_event(&widget, EventType::ButtonRelease, LEFT_BUTTON, pressed, to);
+}
+
+/// Get the positions of the pointer moving from `from` to `to`, excluding `from`, in a few steps so
+/// that the drag threshold of GTK is exceeded progressively.
+pub fn drag_path(from: (i32, i32), to: (i32, i32)) -> impl Iterator<Item=(i32, i32)> {
+    (1..=DRAG_STEPS).map(move |step| {
+        (from.0 + (to.0 - from.0) * step / DRAG_STEPS, from.1 + (to.1 - from.1) * step / DRAG_STEPS)
+    })
+}
+
+/// Send a smooth scroll event in the middle of the widget.
+pub fn scroll<W: IsA<Widget>>(widget: &W, delta_x: f64, delta_y: f64) {
+    let widget = widget.upcast_ref();
+    let (window, x, y) = event_position(widget, center(widget));
+    let (root_x, root_y) = root_position(&window, x, y);
+    let mut event = new_event(EventType::Scroll, &window, |event| unsafe {
+        let scroll = &mut (*event).scroll;
+        scroll.x = x;
+        scroll.y = y;
+        scroll.x_root = root_x;
+        scroll.y_root = root_y;
+        scroll.direction = ScrollDirection::Smooth.into_glib();
+        scroll.delta_x = delta_x;
+        scroll.delta_y = delta_y;
+    });
+    event.set_device(pointer(widget).as_ref());
+    gtk::propagate_event(widget, &mut event);
+}
+
+pub fn mouse_move<W: IsA<Widget>>(widget: &W) {
+    let widget = widget.upcast_ref();
+    motion_event(widget, ModifierType::empty(), center(widget));
+}
+
+/// Give the focus to the widget, even when its window is not focused by the window manager.
+pub fn focus<W: IsA<Widget>>(widget: &W) {
+    let widget = widget.upcast_ref();
+    if let Some(window) = widget.toplevel().and_then(|toplevel| toplevel.window()) {
+        let mut event = new_event(EventType::FocusChange, &window, |event| unsafe {
+            (*event).focus_change.in_ = 1;
+        });
+        gtk::main_do_event(&mut event);
+    }
+    widget.grab_focus();
+}
+
+pub fn key_press<W: IsA<Widget>>(widget: &W, key: Key) {
+    key_event(widget.upcast_ref(), EventType::KeyPress, key, ModifierType::empty());
+}
+
+pub fn key_release<W: IsA<Widget>>(widget: &W, key: Key) {
+    key_event(widget.upcast_ref(), EventType::KeyRelease, key, ModifierType::empty());
+}
+
+pub fn enter_key<W: IsA<Widget>>(widget: &W, key: Key) {
+    key_press(widget, key.clone());
+    key_release(widget, key);
+}
+
+/// Press and release `key` while the `modifiers` keys are pressed.
+/// Only the state of the events of `key` contains the modifiers: no events are sent for the
+/// modifier keys themselves.
+pub fn key_combo<W: IsA<Widget>>(widget: &W, key: Key, modifiers: ModifierType) {
+    let widget = widget.upcast_ref();
+    key_event(widget, EventType::KeyPress, key.clone(), modifiers);
+    key_event(widget, EventType::KeyRelease, key, modifiers);
+}
+
+pub fn char_to_key(char: char) -> Key {
+    match char {
+        '\n' => key::Return,
+        '\t' => key::Tab,
+        _ => Key::from_unicode(char),
+    }
+}
+
+fn button_event(widget: &Widget, typ: EventType, button: u32, state: ModifierType, position: (i32, i32)) {
+    let (window, x, y) = event_position(widget, position);
+    let (root_x, root_y) = root_position(&window, x, y);
+    let mut event = new_event(typ, &window, |event| unsafe {
+        let event_button = &mut (*event).button;
+        event_button.x = x;
+        event_button.y = y;
+        event_button.x_root = root_x;
+        event_button.y_root = root_y;
+        event_button.state = state.into_glib();
+        event_button.button = button;
+    });
+    event.set_device(pointer(widget).as_ref());
+    gtk::propagate_event(widget, &mut event);
+}
+
+fn motion_event(widget: &Widget, state: ModifierType, position: (i32, i32)) {
+    let (window, x, y) = event_position(widget, position);
+    let (root_x, root_y) = root_position(&window, x, y);
+    let mut event = new_event(EventType::MotionNotify, &window, |event| unsafe {
+        let motion = &mut (*event).motion;
+        motion.x = x;
+        motion.y = y;
+        motion.x_root = root_x;
+        motion.y_root = root_y;
+        motion.state = state.into_glib();
+    });
+    event.set_device(pointer(widget).as_ref());
+    gtk::propagate_event(widget, &mut event);
+}
+
+fn button_mask(button: u32) -> ModifierType {
+    match button {
+        LEFT_BUTTON => ModifierType::BUTTON1_MASK,
+        MIDDLE_BUTTON => ModifierType::BUTTON2_MASK,
+        RIGHT_BUTTON => ModifierType::BUTTON3_MASK,
+        _ => ModifierType::empty(),
+    }
+}
+
+/// The key events are sent to the toplevel window, which forwards them to the focused widget, like
+/// the real key events.
+fn key_event(widget: &Widget, typ: EventType, key: Key, state: ModifierType) {
+    let window = widget.toplevel()
+        .and_then(|toplevel| toplevel.window())
+        .expect("toplevel window of the widget");
+    let keyval = *key;
+    let keymap_key = gdk::Keymap::for_display(&window.display())
+        .and_then(|keymap| keymap.entries_for_keyval(keyval).into_iter().next());
+    let mut event = new_event(typ, &window, |event| unsafe {
+        let key = &mut (*event).key;
+        key.keyval = keyval;
+        key.state = state.into_glib();
+        if let Some(ref keymap_key) = keymap_key {
+            key.hardware_keycode = keymap_key.keycode() as u16;
+            key.group = keymap_key.group() as u8;
+        }
+    });
+    let keyboard = window.display().default_seat().and_then(|seat| seat.keyboard());
+    event.set_device(keyboard.as_ref());
+    gtk::main_do_event(&mut event);
+}
+
+fn new_event<F: FnOnce(*mut ffi::GdkEvent)>(typ: EventType, window: &Window, init: F) -> gdk::Event {
+    unsafe {
+        let event = ffi::gdk_event_new(typ.into_glib());
+        // The event owns a reference to its window: it is released when the event is freed.
+        (*event).any.window = window.to_glib_full();
+        (*event).any.send_event = 1;
+        // Use the same timestamp for every field of the union, as they are at the same offset.
+        (*event).key.time = (glib::monotonic_time() / 1000) as u32;
+        init(event);
+        gdk::Event::from_glib_full(event)
+    }
+}
+
+/// A tool button does not get the pointer events itself: its inner button does.
+pub fn event_target(widget: &Widget) -> Widget {
+    if let Some(tool_button) = widget.downcast_ref::<ToolButton>() {
+        if let Some(child) = tool_button.upcast_ref::<Bin>().child() {
+            return child;
+        }
+    }
+    widget.clone()
+}
+
+fn center(widget: &Widget) -> (i32, i32) {
+    let allocation = widget.allocation();
+    (allocation.width() / 2, allocation.height() / 2)
+}
+
+/// Get the window receiving the pointer events of the widget and the position in this window of
+/// the `position` relative to the widget.
+fn event_position(widget: &Widget, (mut x, mut y): (i32, i32)) -> (Window, f64, f64) {
+    let window = widget.window().expect("window of the widget");
+    if !widget.has_window() {
+        let allocation = widget.allocation();
+        x += allocation.x();
+        y += allocation.y();
+        // Some widgets, like buttons, get their events from an input-only window.
+        for child in window.children() {
+            let mut user_data = ptr::null_mut();
+            unsafe {
+                ffi::gdk_window_get_user_data(child.to_glib_none().0, &mut user_data);
+            }
+            if user_data == widget.as_ptr() as *mut _ {
+                let (child_x, child_y) = child.position();
+                return (child, (x - child_x) as f64, (y - child_y) as f64);
+            }
+        }
+    }
+    (window, x as f64, y as f64)
+}
+
+fn root_position(window: &Window, x: f64, y: f64) -> (f64, f64) {
+    let (_, origin_x, origin_y) = window.origin();
+    (origin_x as f64 + x, origin_y as f64 + y)
+}
+
+fn pointer(widget: &Widget) -> Option<gdk::Device> {
+    widget.display().default_seat().and_then(|seat| seat.pointer())
+}
@@ -0,0 +1,103 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Support for the two-way bindings (`property <=> self.model.attribute`) of the `view!` macro.
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use glib::Value;
+
+type Dispatcher = Rc<RefCell<Option<Rc<dyn Fn()>>>>;
+
+/// Writes to the model coming from the widgets of two-way bindings.
+///
+/// The model is owned by the component, so the signal handlers of the widgets cannot modify it
+/// directly: the new values are queued with the name of the model attribute and the component is
+/// asked to write them through the dispatcher, which goes through the same code as an assignment
+/// in `update()` so that the other properties depending on these attributes are updated as well.
+/// While `update()` is executing, the writes are ignored since the changes of the properties come
+/// from the model itself: this avoids feedback loops.
+///
+/// This is used internally by the `#[widget]` attribute.
+#[doc(hidden)]
+#[derive(Clone)]
+pub struct ModelBindings {
+    blocked: Rc<Cell<bool>>,
+    dispatcher: Dispatcher,
+    writes: Rc<RefCell<Vec<(&'static str, Value)>>>,
+}
+
+impl ModelBindings {
+    /// Create an empty queue of model writes.
+    pub fn new() -> Self {
+        ModelBindings {
+            blocked: Rc::new(Cell::new(false)),
+            dispatcher: Rc::new(RefCell::new(None)),
+            writes: Rc::new(RefCell::new(vec![])),
+        }
+    }
+
+    /// Block the new writes until the returned guard is dropped.
+    pub fn block(&self) -> BindingsGuard {
+        let was_blocked = self.blocked.replace(true);
+        BindingsGuard {
+            blocked: self.blocked.clone(),
+            was_blocked,
+        }
+    }
+
+    /// Queue a write of `value` to the model `attribute`, unless the bindings are currently
+    /// blocked, and ask the component to apply it.
+    pub fn push(&self, attribute: &'static str, value: Value) {
+        if !self.blocked.get() {
+            self.writes.borrow_mut().push((attribute, value));
+            // Clone the dispatcher so that it can set a new one while it is executing.
+            let dispatcher = self.dispatcher.borrow().clone();
+            if let Some(dispatcher) = dispatcher {
+                dispatcher();
+            }
+        }
+    }
+
+    /// Set the function called when a new write is queued.
+    pub fn set_dispatcher<F: Fn() + 'static>(&self, dispatcher: F) {
+        *self.dispatcher.borrow_mut() = Some(Rc::new(dispatcher));
+    }
+
+    /// Take the queued writes.
+    pub fn take_writes(&self) -> Vec<(&'static str, Value)> {
+        self.writes.borrow_mut().drain(..).collect()
+    }
+}
+
+/// Guard unblocking the writes of the bindings when dropped.
+#[doc(hidden)]
+pub struct BindingsGuard {
+    blocked: Rc<Cell<bool>>,
+    was_blocked: bool,
+}
+
+impl Drop for BindingsGuard {
+    fn drop(&mut self) {
+        self.blocked.set(self.was_blocked);
+    }
+}
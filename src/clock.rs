@@ -0,0 +1,294 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Clock used by the timers of relm: [`interval`](../fn.interval.html),
+//! [`timeout`](../fn.timeout.html) and the `.debounce(ms)` modifier of the `view!` macro.
+//!
+//! By default, the timers are glib timeouts.
+//! In tests, a virtual clock can be enabled on the current thread with [`enable_virtual`]: the
+//! timers created afterwards only fire when the clock is moved forward with [`advance`].
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use glib::{Continue, SourceId};
+
+type Callback = Rc<RefCell<Box<dyn FnMut() -> Continue>>>;
+
+struct Timer {
+    callback: Callback,
+    deadline: Duration,
+    id: u64,
+    interval: Duration,
+}
+
+struct VirtualClock {
+    elapsed: Duration,
+    next_id: u64,
+    timers: Vec<Timer>,
+}
+
+impl VirtualClock {
+    fn new() -> Self {
+        VirtualClock {
+            elapsed: Duration::ZERO,
+            next_id: 0,
+            timers: vec![],
+        }
+    }
+
+    /// Get the timer with the earliest deadline which is not later than `target`.
+    /// Timers with the same deadline fire in the order they were added.
+    fn next_due(&self, target: Duration) -> Option<(u64, Duration, Callback)> {
+        self.timers.iter()
+            .filter(|timer| timer.deadline <= target)
+            .min_by_key(|timer| (timer.deadline, timer.id))
+            .map(|timer| (timer.id, timer.deadline, timer.callback.clone()))
+    }
+
+    fn remove(&mut self, id: u64) {
+        self.timers.retain(|timer| timer.id != id);
+    }
+}
+
+thread_local! {
+    static VIRTUAL_CLOCK: RefCell<Option<VirtualClock>> = const { RefCell::new(None) };
+}
+
+/// Identifier of a timer, used to cancel it.
+pub(crate) enum TimerId {
+    Glib(SourceId),
+    Virtual(u64),
+}
+
+impl TimerId {
+    /// Cancel the timer.
+    /// Unlike `SourceId::remove()`, this should not be called after a one-shot timer fired.
+    pub(crate) fn remove(self) {
+        match self {
+            TimerId::Glib(source) => source.remove(),
+            TimerId::Virtual(id) => {
+                try_with_virtual_clock(|clock| clock.remove(id));
+            },
+        }
+    }
+}
+
+/// Call `callback` every `interval` until it returns `Continue(false)`.
+pub(crate) fn add_timer<F: FnMut() -> Continue + 'static>(interval: Duration, callback: F) -> TimerId {
+    VIRTUAL_CLOCK.with(|clock| {
+        match *clock.borrow_mut() {
+            Some(ref mut clock) => {
+                let id = clock.next_id;
+                clock.next_id += 1;
+                clock.timers.push(Timer {
+                    callback: Rc::new(RefCell::new(Box::new(callback))),
+                    deadline: clock.elapsed + interval,
+                    id,
+                    interval,
+                });
+                TimerId::Virtual(id)
+            },
+            None => TimerId::Glib(glib::timeout_add_local(interval, callback)),
+        }
+    })
+}
+
+/// Use a virtual clock, starting at zero, for the timers created afterwards on the current thread.
+/// The virtual timers previously created are dropped.
+/// The glib timers previously created still fire in real time.
+pub fn enable_virtual() {
+    VIRTUAL_CLOCK.with(|clock| *clock.borrow_mut() = Some(VirtualClock::new()));
+}
+
+/// Go back to real time for the timers created afterwards on the current thread.
+/// The virtual timers are dropped without firing.
+pub fn disable_virtual() {
+    VIRTUAL_CLOCK.with(|clock| clock.borrow_mut().take());
+}
+
+/// Check whether the timers of the current thread use a virtual clock.
+pub fn is_virtual() -> bool {
+    VIRTUAL_CLOCK.with(|clock| clock.borrow().is_some())
+}
+
+/// Get the time elapsed on the virtual clock since it was enabled.
+///
+/// # Panics
+///
+/// Panics if the virtual clock is not enabled on the current thread.
+pub fn elapsed() -> Duration {
+    with_virtual_clock(|clock| clock.elapsed)
+}
+
+/// Get the number of virtual timers which did not fire yet or which are still repeating.
+///
+/// # Panics
+///
+/// Panics if the virtual clock is not enabled on the current thread.
+pub fn pending_timers() -> usize {
+    with_virtual_clock(|clock| clock.timers.len())
+}
+
+/// Move the virtual clock forward by `duration`, synchronously firing the timers that become due,
+/// in the order of their deadlines.
+/// An interval fires as many times as its period fits in `duration`.
+/// The timers created by the fired callbacks also fire if they become due before the end of
+/// `duration`.
+///
+/// The messages emitted by the timers are only processed when the main loop runs.
+///
+/// # Panics
+///
+/// Panics if the virtual clock is not enabled on the current thread.
+pub fn advance(duration: Duration) {
+    let target = with_virtual_clock(|clock| clock.elapsed + duration);
+    while let Some((id, deadline, callback)) = try_with_virtual_clock(|clock| clock.next_due(target)).flatten() {
+        try_with_virtual_clock(|clock| clock.elapsed = deadline);
+        // The clock is not borrowed while the callback runs since it can add or remove timers, or
+        // even disable the virtual clock.
+        let Continue(repeat) = (callback.borrow_mut())();
+        try_with_virtual_clock(|clock| {
+            if let Some(index) = clock.timers.iter().position(|timer| timer.id == id) {
+                if repeat {
+                    let timer = &mut clock.timers[index];
+                    timer.deadline += timer.interval;
+                    if timer.interval.is_zero() {
+                        // Like glib, which calls them once per main loop iteration, only call the
+                        // timers with a zero interval once per call to advance().
+                        timer.deadline = target + Duration::from_nanos(1);
+                    }
+                }
+                else {
+                    clock.timers.remove(index);
+                }
+            }
+        });
+    }
+    try_with_virtual_clock(|clock| clock.elapsed = target);
+}
+
+fn try_with_virtual_clock<F: FnOnce(&mut VirtualClock) -> T, T>(func: F) -> Option<T> {
+    VIRTUAL_CLOCK.with(|clock| clock.borrow_mut().as_mut().map(func))
+}
+
+fn with_virtual_clock<F: FnOnce(&mut VirtualClock) -> T, T>(func: F) -> T {
+    try_with_virtual_clock(func).expect("the virtual clock is not enabled on this thread")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::time::Duration;
+
+    use glib::Continue;
+
+    use super::{add_timer, advance, elapsed, enable_virtual, pending_timers, TimerId};
+
+    type Log = Rc<RefCell<Vec<&'static str>>>;
+
+    fn log_timer(log: &Log, interval: u64, name: &'static str, repeat: bool) -> TimerId {
+        let log = log.clone();
+        add_timer(Duration::from_millis(interval), move || {
+            log.borrow_mut().push(name);
+            Continue(repeat)
+        })
+    }
+
+    #[test]
+    fn deadline_order() {
+        enable_virtual();
+        let log = Log::default();
+        let _ = log_timer(&log, 30, "30ms", false);
+        let _ = log_timer(&log, 10, "10ms", false);
+        let _ = log_timer(&log, 20, "20ms", false);
+
+        advance(Duration::from_millis(15));
+        assert_eq!(*log.borrow(), ["10ms"]);
+        assert_eq!(elapsed(), Duration::from_millis(15));
+
+        advance(Duration::from_millis(15));
+        assert_eq!(*log.borrow(), ["10ms", "20ms", "30ms"]);
+        assert_eq!(pending_timers(), 0);
+    }
+
+    #[test]
+    fn same_deadline_in_creation_order() {
+        enable_virtual();
+        let log = Log::default();
+        let _ = log_timer(&log, 10, "first", false);
+        let _ = log_timer(&log, 10, "second", false);
+        let _ = log_timer(&log, 10, "third", false);
+
+        advance(Duration::from_millis(10));
+        assert_eq!(*log.borrow(), ["first", "second", "third"]);
+    }
+
+    #[test]
+    fn interval_fires_several_times() {
+        enable_virtual();
+        let log = Log::default();
+        let _ = log_timer(&log, 10, "interval", true);
+        let _ = log_timer(&log, 25, "timeout", false);
+
+        advance(Duration::from_millis(35));
+        assert_eq!(*log.borrow(), ["interval", "interval", "timeout", "interval"]);
+        assert_eq!(pending_timers(), 1);
+    }
+
+    #[test]
+    fn zero_interval_fires_once_per_advance() {
+        enable_virtual();
+        let log = Log::default();
+        let _ = log_timer(&log, 0, "idle", true);
+
+        advance(Duration::from_millis(10));
+        assert_eq!(log.borrow().len(), 1);
+
+        advance(Duration::from_millis(10));
+        assert_eq!(log.borrow().len(), 2);
+    }
+
+    #[test]
+    fn remove_from_callback() {
+        enable_virtual();
+        let log = Log::default();
+        let removed: Rc<RefCell<Option<TimerId>>> = Rc::new(RefCell::new(None));
+        {
+            let log = log.clone();
+            let removed = removed.clone();
+            let _ = add_timer(Duration::from_millis(10), move || {
+                log.borrow_mut().push("remover");
+                if let Some(id) = removed.borrow_mut().take() {
+                    id.remove();
+                }
+                Continue(true)
+            });
+        }
+        *removed.borrow_mut() = Some(log_timer(&log, 10, "removed", true));
+
+        advance(Duration::from_millis(30));
+        assert_eq!(*log.borrow(), ["remover", "remover", "remover"]);
+        assert_eq!(pending_timers(), 1);
+    }
+}
@@ -0,0 +1,75 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Support for the `.debounce(ms)` modifier of the event handlers of the `view!` macro.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use glib::Continue;
+
+use crate::clock::{self, TimerId};
+use crate::core::StreamHandle;
+
+/// Delay the messages sent by an event handler until no event was emitted for some time.
+///
+/// This is used internally by the `#[widget]` attribute.
+#[doc(hidden)]
+pub struct Debounce<MSG> {
+    delay: u32,
+    source: Rc<RefCell<Option<TimerId>>>,
+    stream: StreamHandle<MSG>,
+}
+
+impl<MSG: 'static> Debounce<MSG> {
+    /// Create a debouncer sending the messages to `stream` after `delay` ms.
+    pub fn new(stream: &StreamHandle<MSG>, delay: u32) -> Self {
+        Debounce {
+            delay,
+            source: Rc::new(RefCell::new(None)),
+            stream: stream.clone(),
+        }
+    }
+
+    /// Send the `msg`, if any, after the delay, cancelling the message previously scheduled.
+    /// Returns `None` since the message is not sent immediately.
+    pub fn emit(&self, msg: Option<MSG>) -> Option<MSG> {
+        if let Some(msg) = msg {
+            if let Some(source) = self.source.borrow_mut().take() {
+                source.remove();
+            }
+            let stream = self.stream.clone();
+            let source = self.source.clone();
+            let mut msg = Some(msg);
+            let id = clock::add_timer(Duration::from_millis(self.delay as u64), move || {
+                // The timer is removed after this call.
+                let _ = source.borrow_mut().take();
+                if let Some(msg) = msg.take() {
+                    stream.emit(msg);
+                }
+                Continue(false)
+            });
+            *self.source.borrow_mut() = Some(id);
+        }
+        None
+    }
+}
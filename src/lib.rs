@@ -50,17 +50,10 @@
 )]
 
 /*
- * TODO: allow using self in the event connection right side (messages to be sent) to remove the
- * with syntax.
- *
  * TODO: improve README so that examples can be copy/pasted.
  *
  * FIXME: some relm widgets requires { and } (see the rusic music-player) while other do not.
  * FIXME: should not require to import WidgetExt because it calls show().
- * FIXME: cannot have relm event with tuple as a value like:
- * RelmWidget {
- *     RelmEvent((value1, value2)) => …
- * }
  * TODO: use pub(crate) instead of pub so that we're not bound to make the model and msg structs pub.
  *
  * TODO: add init() method to the Widget (or Update) trait as a shortcut for init::<Widget>()?
@@ -69,24 +62,16 @@
  * FIXME: cannot add a trailing coma at the end of a initializer list.
  * TODO: switch from gtk::main() to MainLoop to avoid issues with nested loops.
  * TODO: prefix generated container name with _ to hide warnings.
- * TODO: remove the code generation related to using self in event handling.
  * TODO: remove the closure transformer code.
  *
  * TODO: move most of the examples in the tests/ directory.
- * TODO: add construct-only properties for relm widget (to replace initial parameters) to allow
- * setting them by name (or with default value).
  * TODO: find a way to do two-step initialization (to avoid using unitialized in model()).
- * TODO: consider using GBinding instead of manually adding calls to set_property().
  *
  * TODO: add a FAQ with the question related to getting an error when not importing the gtk traits.
  *
  * TODO: show a warning when components are destroyed after the end of call to Widget::view().
- * TODO: warn in the attribute when an event cycle is found.
  * TODO: add a Deref<Widget> for Component?
  * TODO: look at how Elm works with the <canvas> element.
- * TODO: the widget names should start with __relm_field_.
- *
- * TODO: reset widget name counters when creating new widget?
  *
  * TODO: refactor the code.
  *
@@ -96,13 +81,18 @@
  * TODO: optionnaly multi-threaded.
  */
 
+mod binding;
+pub mod clock;
 mod component;
 mod container;
 mod core;
+mod debounce;
 mod drawing;
 mod macros;
+mod mirror;
 mod state;
 mod widget;
+mod widget_name;
 
 #[doc(hidden)]
 pub use fragile::Fragile;
@@ -112,6 +102,7 @@ pub use glib::{
     Cast,
     IsA,
     Object,
+    ObjectExt,
     StaticType,
     ToValue,
     Value,
@@ -119,7 +110,16 @@ pub use glib::{
 #[doc(hidden)]
 pub use glib::translate::{FromGlibPtrNone, IntoGlib, ToGlibPtr};
 #[doc(hidden)]
-pub use gobject_sys::{GParameter, g_object_newv};
+pub use binding::{BindingsGuard, ModelBindings};
+#[doc(hidden)]
+pub use debounce::Debounce;
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub use serde;
+#[doc(hidden)]
+pub use mirror::ModelMirrors;
+#[doc(hidden)]
+pub use widget_name::{add_widget_name, widget_names};
 use glib::Continue;
 
 pub use crate::core::{Channel, EventStream, Sender, StreamHandle};
@@ -127,6 +127,7 @@ pub use crate::state::{
     DisplayVariant,
     IntoOption,
     IntoPair,
+    ModelParams,
     Relm,
     Update,
     UpdateNew,
@@ -353,7 +354,7 @@ pub fn run<WIDGET>(model_param: WIDGET::ModelParam) -> Result<(), glib::BoolErro
 /// Emit the `msg` every `duration` ms.
 pub fn interval<F: Fn() -> MSG + 'static, MSG: 'static>(stream: &StreamHandle<MSG>, duration: u32, constructor: F) {
     let stream = stream.clone();
-    glib::timeout_add_local(std::time::Duration::from_millis(duration as u64), move || {
+    clock::add_timer(std::time::Duration::from_millis(duration as u64), move || {
         let msg = constructor();
         stream.emit(msg);
         Continue(true)
@@ -363,7 +364,7 @@ pub fn interval<F: Fn() -> MSG + 'static, MSG: 'static>(stream: &StreamHandle<MS
 /// After `duration` ms, emit `msg`.
 pub fn timeout<F: Fn() -> MSG + 'static, MSG: 'static>(stream: &StreamHandle<MSG>, duration: u32, constructor: F) {
     let stream = stream.clone();
-    glib::timeout_add_local(std::time::Duration::from_millis(duration as u64), move || {
+    clock::add_timer(std::time::Duration::from_millis(duration as u64), move || {
         let msg = constructor();
         stream.emit(msg);
         Continue(false)
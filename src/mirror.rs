@@ -0,0 +1,80 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Support for reading the model in the event handlers (`clicked => Save(self.model.id)`) of the
+//! `view!` macro.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::{Rc, Weak};
+
+type MirrorUpdate<MODEL> = Box<dyn Fn(&MODEL)>;
+
+/// Copies of the model attributes read by the event handlers.
+///
+/// The model is owned by the component, so the signal handlers of the widgets cannot read it
+/// directly: they read a copy of the attributes instead, which is updated whenever the attribute
+/// is assigned in `update()` and after each call to `update()`, in case it was mutated in place.
+/// The copies are owned by the signal handlers, so they are dropped with the widgets.
+///
+/// This is used internally by the `#[widget]` attribute.
+#[doc(hidden)]
+pub struct ModelMirrors<MODEL> {
+    updates: HashMap<&'static str, MirrorUpdate<MODEL>>,
+}
+
+impl<MODEL> ModelMirrors<MODEL> {
+    /// Create an empty set of copies.
+    pub fn new() -> Self {
+        ModelMirrors {
+            updates: HashMap::new(),
+        }
+    }
+
+    /// Create a copy of the model `attribute`, as returned by `get`.
+    pub fn mirror<T, F>(&mut self, attribute: &'static str, model: &MODEL, get: F) -> Rc<RefCell<T>>
+    where T: 'static,
+          F: Fn(&MODEL) -> T + 'static,
+    {
+        let value = Rc::new(RefCell::new(get(model)));
+        let mirror: Weak<RefCell<T>> = Rc::downgrade(&value);
+        self.updates.insert(attribute, Box::new(move |model| {
+            if let Some(mirror) = mirror.upgrade() {
+                *mirror.borrow_mut() = get(model);
+            }
+        }));
+        value
+    }
+
+    /// Update the copy of the model `attribute`.
+    pub fn update(&self, attribute: &str, model: &MODEL) {
+        if let Some(update) = self.updates.get(attribute) {
+            update(model);
+        }
+    }
+
+    /// Update the copies of all the attributes.
+    pub fn update_all(&self, model: &MODEL) {
+        for update in self.updates.values() {
+            update(model);
+        }
+    }
+}
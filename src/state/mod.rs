@@ -35,9 +35,12 @@
 mod into;
 mod macros;
 
+use std::cell::RefCell;
+use std::rc::Rc;
 use std::time::SystemTime;
 
 pub use crate::core::{EventStream, StreamHandle};
+use crate::binding::ModelBindings;
 
 pub use self::into::{IntoOption, IntoPair};
 
@@ -93,6 +96,19 @@ pub trait Update
 
     /// Method called when a message is received from an event.
     fn update(&mut self, event: Self::Msg);
+
+    /// Get the queue of the writes coming from the two-way bindings.
+    /// This is used internally by the `#[widget]` attribute.
+    #[doc(hidden)]
+    fn model_bindings(&self) -> Option<&ModelBindings> {
+        None
+    }
+
+    /// Write the values coming from the two-way bindings into the model.
+    /// This is used internally by the `#[widget]` attribute.
+    #[doc(hidden)]
+    fn sync_bindings(&mut self) {
+    }
 }
 
 /// Trait for an `Update` object that can be created directly.
@@ -102,6 +118,18 @@ pub trait UpdateNew: Update {
     fn new(_relm: &Relm<Self>, _model: Self::Model) -> Self;
 }
 
+/// Trait for the params struct generated by `#[widget]` when the `model()` method has named
+/// parameters.
+///
+/// This is used internally by the library to set the parameters by name in the `view!` macro.
+pub trait ModelParams {
+    /// The type of the builder of the params struct.
+    type Builder;
+
+    /// Create a builder where every parameter is unset.
+    fn builder() -> Self::Builder;
+}
+
 /// Format trait for enum variants.
 ///
 /// `DisplayVariant` is similar to `Debug`, but only works on enum and does not list the
@@ -141,8 +169,21 @@ pub fn init_component<UPDATE>(stream: &EventStream<UPDATE::Msg>, mut component:
           UPDATE::Msg: DisplayVariant + 'static,
 {
     component.subscriptions(relm);
+    let component = Rc::new(RefCell::new(component));
+    if let Some(bindings) = component.borrow().model_bindings() {
+        let weak_component = Rc::downgrade(&component);
+        bindings.set_dispatcher(move || {
+            // When the component is already borrowed, the write stays queued and is applied at the
+            // start of the next update().
+            if let Some(component) = weak_component.upgrade() {
+                if let Ok(mut component) = component.try_borrow_mut() {
+                    component.sync_bindings();
+                }
+            }
+        });
+    }
     stream.set_callback(move |event| {
-        update_component(&mut component, event);
+        update_component(&mut *component.borrow_mut(), event);
     });
 }
 
@@ -0,0 +1,55 @@
+/*
+ * Copyright (c) 2020 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Extra names of the widgets, given by the `#[name]` attribute, used to find them in the tests.
+
+use glib::ObjectExt;
+
+const NAMES_KEY: &str = "relm-widget-names";
+
+/// Add `name` to the extra names of `widget`.
+///
+/// The root of a relm widget keeps the name given in its own view as its buildable name, so the
+/// name given by the parent widget is added this way.
+///
+/// This is used internally by the `#[widget]` attribute.
+#[doc(hidden)]
+pub fn add_widget_name(widget: &gtk::Widget, name: &str) {
+    let mut names = widget_names(widget);
+    names.push(name.to_string());
+    // Safety: this key is only used with a Vec<String>.
+    unsafe {
+        widget.set_data(NAMES_KEY, names);
+    }
+}
+
+/// Get the extra names of `widget`, added by the `#[name]` attribute of the parent widgets.
+///
+/// This is used by relm-test to find the widgets by name.
+#[doc(hidden)]
+pub fn widget_names(widget: &gtk::Widget) -> Vec<String> {
+    // Safety: this key is only used with a Vec<String>.
+    unsafe {
+        widget.data::<Vec<String>>(NAMES_KEY)
+            .map(|names| names.as_ref().clone())
+            .unwrap_or_default()
+    }
+}